@@ -0,0 +1,847 @@
+/// This file contains the typed lookups exposed by the library
+use crate::cache::Cache;
+use crate::champ::Champions;
+use crate::client::{Endpoint, RetryPolicy, RiotClient};
+use crate::error::{ProgramError, Result};
+use crate::items::Items;
+use crate::json::{ChampionMasteryJSON, GameJSON, MatchDataJSON, RiotAccountJSON};
+use crate::models::{
+    Account, Ban, ChampionPool, ChampionStats, Game, HistoryFilter, Mastery, MatchDetails,
+    MatchTeam, Participant, PlayerMasteries, PlayerStats, Rank, Side, UserAccount, UserGames,
+    UserMatch,
+};
+use crate::queue::Queues;
+use crate::region::Region;
+use crate::runes::Runes;
+use crate::spells::Spells;
+use futures::future::{join, join3, join4, join_all};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+// Number of mastery champions shown with a profile
+const PROFILE_MASTERIES: usize = 3;
+// The most match ids Match-V5 returns in one request
+const MATCH_IDS_PAGE: u16 = 100;
+// Recent games two players on the same team must share to be shown as premade
+const PREMADE_SHARED_GAMES: usize = 2;
+
+// Looks up players on a single region. Requests share one rate limiter, cache and copy of the
+// static data.
+pub struct Client {
+    riot: RiotClient,
+    static_data: Mutex<Option<Arc<StaticData>>>,
+}
+
+// The champion, queue, rune, item and spell names lookups are shown with
+struct StaticData {
+    champs: Champions,
+    queues: Queues,
+    runes: Runes,
+    items: Items,
+    spells: Spells,
+}
+
+impl Client {
+    // A client with the default retry policy and the on-disk cache enabled
+    pub fn new(api_key: &str, region: Region) -> Self {
+        Client::with_options(api_key, region, RetryPolicy::default(), Cache::new(true))
+    }
+
+    pub fn with_options(api_key: &str, region: Region, retry: RetryPolicy, cache: Cache) -> Self {
+        Client {
+            riot: RiotClient::new(api_key, region, retry, cache),
+            static_data: Mutex::new(None),
+        }
+    }
+
+    pub fn region(&self) -> Region {
+        self.riot.region()
+    }
+
+    // Resolves a Riot ID, e.g. "Faker#KR1", to the summoner on the client's region
+    pub async fn summoner(&self, riot_id: &str) -> Result<Account> {
+        let riot_account = self.riot_account(riot_id).await?;
+        let url = format!(
+            "{}/lol/summoner/v4/summoners/by-puuid/{}",
+            self.region().host(),
+            riot_account.puuid
+        );
+        let res = self.riot.get(&url, Endpoint::Summoner).await?;
+
+        match res.status {
+            200 => {
+                let mut account: Account = res.json()?;
+                account.name = format!("{}#{}", riot_account.gameName, riot_account.tagLine);
+                Ok(account)
+            }
+            // The Riot account exists but has never played League on this region
+            404 => Err(ProgramError::InvalidAccount),
+            _ => Err(ProgramError::BadResponse),
+        }
+    }
+
+    // Returns the rank in every queue the player is placed in, solo queue first
+    pub async fn ranks(&self, account: &Account) -> Result<Vec<Rank>> {
+        self.ranks_by_puuid(&account.puuid).await
+    }
+
+    // Returns the account's rank together with its most played role over the recent games
+    pub async fn profile(&self, riot_id: &str) -> Result<UserAccount> {
+        let account = self.summoner(riot_id).await?;
+        let filter = HistoryFilter::default();
+        let ranks = self.ranks(&account);
+        let matches = self.recent_matches(&account.puuid, &filter);
+        let masteries = self.masteries(&account);
+        match join3(ranks, matches, masteries).await {
+            (Ok(ranks), Ok(matches), masteries) => {
+                let role = most_played_role(&matches, &account.puuid);
+                // Mastery is extra information, the profile is still shown without it
+                let mut top = masteries.unwrap_or_default();
+                top.truncate(PROFILE_MASTERIES);
+                Ok(UserAccount::new(account, ranks, role, top))
+            }
+            (Err(e), _, _) | (_, Err(e), _) => Err(e),
+        }
+    }
+
+    pub async fn active_game(&self, account: &Account) -> Result<Game> {
+        let url = format!(
+            "{}/lol/spectator/v5/active-games/by-summoner/{}",
+            self.region().host(),
+            account.puuid
+        );
+        let res = self.riot.get(&url, Endpoint::ActiveGame).await?;
+        let json: GameJSON = match res.status {
+            404 => return Err(ProgramError::NotInGame),
+            200 => res.json()?,
+            _ => return Err(ProgramError::InvalidResponse),
+        };
+
+        let ranks = json
+            .participants
+            .iter()
+            .map(|p| async move {
+                // Bots have no puuid and so no rank to look up
+                match p.puuid().is_empty() {
+                    true => Ok(Vec::new()),
+                    false => self.ranks_by_puuid(p.puuid()).await,
+                }
+            })
+            .collect::<Vec<_>>();
+        let (result, data) = join(join_all(ranks), self.static_data()).await;
+        let (champs, queues, runes, spells) =
+            (&data.champs, &data.queues, &data.runes, &data.spells);
+
+        let mut players: Vec<Participant> = Vec::new();
+        for (player, data) in json.participants.iter().zip(result) {
+            let ranks = match data {
+                Ok(ranks) => ranks,
+                // Riot has no league entries for the player
+                Err(ProgramError::InvalidAccount) => Vec::new(),
+                Err(e) => return Err(e),
+            };
+            // A flex lobby shows flex ranks, other queues fall back to solo queue
+            let rank = Rank::for_queue(&ranks, json.gameQueueConfigId);
+            let champ = champs.name(player.championId).to_string();
+            let side = Side::from_ids(player.teamId, player.playerSubteamId);
+            let name = player.riot_id().to_string();
+            let mut participant = Participant::new(side, name, rank, player.championId, champ);
+            participant.puuid = player.puuid().to_string();
+            participant.searched = player.puuid() == account.puuid;
+            participant.spells = [player.spell1Id, player.spell2Id]
+                .iter()
+                .filter(|id| **id > 0)
+                .map(|id| spells.name(*id))
+                .collect();
+            // Bots have no runes
+            let rune = |id: Option<u16>| id.map(|id| runes.name(id).to_string());
+            participant.keystone = rune(player.keystone()).unwrap_or_default();
+            participant.secondary_tree = rune(player.secondary_tree()).unwrap_or_default();
+            players.push(participant);
+        }
+
+        let mut bans = json.bannedChampions;
+        bans.sort_by_key(|b| b.pickTurn);
+        let bans = bans
+            .iter()
+            .filter(|b| b.championId > 0)
+            .map(|b| {
+                let champion_id = b.championId as u16;
+                let ban = Ban {
+                    champion_id,
+                    champion: champs.name(champion_id).to_string(),
+                };
+                (Side::from_ids(b.teamId, 0), ban)
+            })
+            .collect();
+        let mut game = Game::new(players, bans, json.gameMode, json.gameType);
+        game.queue_id = json.gameQueueConfigId;
+        game.queue = queues.name(json.gameQueueConfigId);
+        game.map_id = json.mapId;
+        game.map = queues.map_name(json.mapId);
+        game.started = json.gameStartTime;
+        game.length = json.gameLength.max(0) as u32;
+        Ok(game)
+    }
+
+    pub async fn match_history(
+        &self,
+        account: &Account,
+        filter: &HistoryFilter,
+    ) -> Result<UserGames> {
+        self.games_by_puuid(&account.puuid, &account.name, filter)
+            .await
+    }
+
+    // Fills in each player's games, win rate and mastery on the champion they are playing.
+    // Every player's history is fetched, so this costs up to `filter.count` requests per player.
+    // With `with_premades` the same match ids are also used to mark who queued together.
+    pub async fn live_champion_stats(
+        &self,
+        game: &mut Game,
+        filter: &HistoryFilter,
+        with_premades: bool,
+    ) {
+        let histories = self.live_match_ids(game, filter).await;
+        let futures = game
+            .teams
+            .iter()
+            .flat_map(|t| t.players.iter())
+            .zip(&histories)
+            .map(|(p, ids)| async move {
+                let ids = ids.as_ref().ok()?;
+                Some(
+                    self.champion_stats(&p.puuid, ids, p.champion_id, &p.champion)
+                        .await,
+                )
+            })
+            .collect::<Vec<_>>();
+        // The client queues the requests so the burst stays under the rate limit
+        let stats = join_all(futures).await;
+        let players = game.teams.iter_mut().flat_map(|t| t.players.iter_mut());
+        for (player, stats) in players.zip(stats) {
+            // Bots and players whose history could not be fetched are left without stats
+            player.champion_stats = stats;
+        }
+        if with_premades {
+            mark_premades(game, &histories);
+        }
+    }
+
+    // Marks the players on the same side who shared at least PREMADE_SHARED_GAMES of their last
+    // `filter.count` games. This costs one match id request per player.
+    pub async fn premades(&self, game: &mut Game, filter: &HistoryFilter) {
+        let histories = self.live_match_ids(game, filter).await;
+        mark_premades(game, &histories);
+    }
+
+    // Returns the `count` champions with the most mastery points
+    pub async fn top_masteries(&self, account: &Account, count: usize) -> Result<PlayerMasteries> {
+        let mut masteries = self.masteries(account).await?;
+        masteries.truncate(count);
+        Ok(PlayerMasteries {
+            username: account.name.clone(),
+            masteries,
+        })
+    }
+
+    // Returns every champion the player has mastery on, highest points first
+    pub async fn masteries(&self, account: &Account) -> Result<Vec<Mastery>> {
+        self.masteries_by_puuid(&account.puuid).await
+    }
+
+    // Groups the recent games by champion, optionally with the mastery of each champion
+    pub async fn champion_pool(
+        &self,
+        account: &Account,
+        filter: &HistoryFilter,
+        with_mastery: bool,
+    ) -> Result<ChampionPool> {
+        let mut pool = ChampionPool::from_games(&self.match_history(account, filter).await?);
+        if with_mastery {
+            pool.merge_mastery(&self.masteries(account).await?);
+        }
+        Ok(pool)
+    }
+
+    // Returns the scoreboard of a match. Ids without a platform prefix, e.g. "4961234567", are
+    // looked up on the client's region.
+    pub async fn match_details(&self, match_id: &str) -> Result<MatchDetails> {
+        let match_id = match match_id.contains('_') {
+            true => match_id.trim().to_uppercase(),
+            false => format!("{}_{}", self.region().id().to_uppercase(), match_id.trim()),
+        };
+        let (data, names) = join(self.match_data(&match_id), self.static_data()).await;
+        let data = data?;
+        let (champs, queues, runes, items) =
+            (&names.champs, &names.queues, &names.runes, &names.items);
+
+        let mut teams: Vec<MatchTeam> = Vec::new();
+        for p in &data.info.participants {
+            let player = PlayerStats {
+                name: p.riot_id(),
+                champion_id: p.championId,
+                champion: champs.name(p.championId).to_string(),
+                role: determine_role(&p.teamPosition),
+                level: p.champLevel,
+                kills: p.kills,
+                deaths: p.deaths,
+                assists: p.assists,
+                cs: p.cs(),
+                gold: p.goldEarned,
+                damage: p.totalDamageDealtToChampions,
+                vision_score: p.visionScore,
+                items: p.items().into_iter().map(|id| items.name(id)).collect(),
+                keystone: p
+                    .keystone()
+                    .map(|id| runes.name(id))
+                    .unwrap_or("")
+                    .to_string(),
+                secondary_tree: p
+                    .secondary_tree()
+                    .map(|id| runes.name(id))
+                    .unwrap_or("")
+                    .to_string(),
+            };
+            match teams.iter_mut().find(|t| t.team_id == p.teamId) {
+                Some(team) => team.players.push(player),
+                None => teams.push(MatchTeam {
+                    team_id: p.teamId,
+                    win: p.win,
+                    players: vec![player],
+                }),
+            }
+        }
+        teams.sort_by_key(|t| t.team_id);
+
+        Ok(MatchDetails {
+            remake: data
+                .info
+                .participants
+                .iter()
+                .any(|p| p.gameEndedInEarlySurrender),
+            match_id: data.metadata.matchId,
+            queue_id: data.info.queueId,
+            queue: queues.name(data.info.queueId),
+            map: queues.map_name(data.info.mapId),
+            mode: data.info.gameMode,
+            started: data.info.gameCreation,
+            duration: data.info.gameDuration,
+            teams,
+        })
+    }
+
+    // Loads the static data on first use. The lock is held while loading so lookups running at the
+    // same time wait for one download instead of each starting their own.
+    async fn static_data(&self) -> Arc<StaticData> {
+        let mut data = self.static_data.lock().await;
+        if let Some(data) = data.as_ref() {
+            return Arc::clone(data);
+        }
+        // The other files are taken from the champion data's patch, like `data refresh` does, so
+        // the latest version is only looked up once and a new patch cannot mix the names
+        let champs = Champions::load().await;
+        let version = champs.patch();
+        let (queues, runes, items, spells) = join4(
+            Queues::load(),
+            Runes::load(version),
+            Items::load(version),
+            Spells::load(version),
+        )
+        .await;
+        let loaded = Arc::new(StaticData {
+            champs,
+            queues,
+            runes,
+            items,
+            spells,
+        });
+        *data = Some(Arc::clone(&loaded));
+        loaded
+    }
+
+    // The player's recent games that pass the filter, newest first
+    async fn games_by_puuid(
+        &self,
+        puuid: &str,
+        username: &str,
+        filter: &HistoryFilter,
+    ) -> Result<UserGames> {
+        let ids = self.match_ids(puuid, filter).await?;
+        Ok(self.games_by_ids(puuid, username, &ids).await)
+    }
+
+    // The player's games in the given matches, skipping any that could not be retrieved
+    async fn games_by_ids(&self, puuid: &str, username: &str, match_ids: &[String]) -> UserGames {
+        let (matches, data) = join(self.matches(match_ids), self.static_data()).await;
+        let (champs, queues) = (&data.champs, &data.queues);
+
+        let recent_games = matches
+            .iter()
+            .filter_map(|m| {
+                let p = m.participant(puuid)?;
+                // A remake has no winner so it is reported without an outcome
+                let outcome = match p.gameEndedInEarlySurrender {
+                    true => None,
+                    false => Some(p.win),
+                };
+                let minutes = m.info.gameDuration as f32 / 60.0;
+                Some(UserMatch {
+                    match_id: m.metadata.matchId.clone(),
+                    role: determine_role(&p.teamPosition),
+                    queue_id: m.info.queueId,
+                    queue: queues.name(m.info.queueId),
+                    champion_id: p.championId,
+                    champion: champs.name(p.championId).to_string(),
+                    win: outcome,
+                    kills: p.kills,
+                    deaths: p.deaths,
+                    assists: p.assists,
+                    cs_per_min: ratio(p.cs() as f32, minutes),
+                    kill_participation: ratio(
+                        (p.kills + p.assists) as f32,
+                        m.team_kills(p.teamId) as f32,
+                    ) * 100.0,
+                    damage_share: ratio(
+                        p.totalDamageDealtToChampions as f32,
+                        m.team_damage(p.teamId) as f32,
+                    ) * 100.0,
+                    duration: m.info.gameDuration,
+                })
+            })
+            .collect();
+        UserGames {
+            games: recent_games,
+            username: username.to_string(),
+        }
+    }
+
+    // The recent match ids of every player in the game, in the order of its teams. Bots have no
+    // puuid so nothing is requested for them.
+    async fn live_match_ids(
+        &self,
+        game: &Game,
+        filter: &HistoryFilter,
+    ) -> Vec<Result<Vec<String>>> {
+        let futures = game
+            .teams
+            .iter()
+            .flat_map(|t| t.players.iter())
+            .map(|p| async move {
+                match p.puuid.is_empty() {
+                    true => Err(ProgramError::InvalidAccount),
+                    false => self.match_ids(&p.puuid, filter).await,
+                }
+            })
+            .collect::<Vec<_>>();
+        join_all(futures).await
+    }
+
+    // A player's results and mastery on one champion over the given matches, with no games when
+    // they have not played it
+    async fn champion_stats(
+        &self,
+        puuid: &str,
+        match_ids: &[String],
+        champion_id: u16,
+        champion: &str,
+    ) -> ChampionStats {
+        let games = self.games_by_ids(puuid, "", match_ids);
+        let (games, masteries) = join(games, self.masteries_by_puuid(puuid)).await;
+        let pool = ChampionPool::from_games(&games);
+        let mut stats = pool
+            .champions
+            .into_iter()
+            .find(|c| c.champion_id == champion_id)
+            .unwrap_or_else(|| ChampionStats::new(champion_id, champion.to_string()));
+        // Mastery is extra information, the stats are still shown without it
+        stats.mastery = masteries
+            .unwrap_or_default()
+            .into_iter()
+            .find(|m| m.champion_id == champion_id);
+        stats
+    }
+
+    // The player's masteries, highest points first
+    async fn masteries_by_puuid(&self, puuid: &str) -> Result<Vec<Mastery>> {
+        let url = format!(
+            "{}/lol/champion-mastery/v4/champion-masteries/by-puuid/{}",
+            self.region().host(),
+            puuid
+        );
+        let (res, data) = join(
+            self.riot.get(&url, Endpoint::ChampionMastery),
+            self.static_data(),
+        )
+        .await;
+        let res = res?;
+        let champs = &data.champs;
+        let masteries: Vec<ChampionMasteryJSON> = match res.status {
+            200 => res.json()?,
+            404 => return Err(ProgramError::InvalidAccount),
+            _ => return Err(ProgramError::BadResponse),
+        };
+        let mut masteries: Vec<Mastery> = masteries
+            .into_iter()
+            .map(|m| Mastery {
+                champion_id: m.championId,
+                champion: champs.name(m.championId).to_string(),
+                level: m.championLevel,
+                points: m.championPoints,
+                last_played: m.lastPlayTime,
+                chest_granted: m.chestGranted,
+            })
+            .collect();
+        masteries.sort_by_key(|m| std::cmp::Reverse(m.points));
+        Ok(masteries)
+    }
+
+    async fn ranks_by_puuid(&self, puuid: &str) -> Result<Vec<Rank>> {
+        let url = format!(
+            "{}/lol/league/v4/entries/by-puuid/{}",
+            self.region().host(),
+            puuid
+        );
+        let res = self.riot.get(&url, Endpoint::LeagueEntries).await?;
+
+        match res.status {
+            200 => {
+                let mut ranks: Vec<Rank> = res.json()?;
+                // Known queues in their usual order, anything new after them
+                ranks.sort_by_key(|r| r.queue_type);
+                Ok(ranks)
+            }
+            404 => Err(ProgramError::InvalidAccount),
+            _ => Err(ProgramError::BadResponse),
+        }
+    }
+
+    // Returns the ids of the most recent matches that pass the filter, newest first
+    async fn match_ids(&self, puuid: &str, filter: &HistoryFilter) -> Result<Vec<String>> {
+        if filter.queues.len() <= 1 {
+            return self
+                .match_ids_page(puuid, filter.queues.first(), filter)
+                .await;
+        }
+        // Match-V5 filters on a single queue so each queue is fetched on its own and merged
+        let pages = filter
+            .queues
+            .iter()
+            .map(|queue| self.match_ids_page(puuid, Some(queue), filter))
+            .collect::<Vec<_>>();
+        let mut ids = Vec::new();
+        for page in join_all(pages).await {
+            ids.extend(page?);
+        }
+        ids.sort_by_key(|id| std::cmp::Reverse(match_number(id)));
+        ids.dedup();
+        ids.truncate(filter.count as usize);
+        Ok(ids)
+    }
+
+    // Pages through the ids of one queue, or every queue, until `filter.count` ids are found
+    async fn match_ids_page(
+        &self,
+        puuid: &str,
+        queue: Option<&u16>,
+        filter: &HistoryFilter,
+    ) -> Result<Vec<String>> {
+        let mut ids: Vec<String> = Vec::new();
+        while ids.len() < filter.count as usize {
+            let count = (filter.count - ids.len() as u16).min(MATCH_IDS_PAGE);
+            let mut url = format!(
+                "{}/lol/match/v5/matches/by-puuid/{}/ids?start={}&count={}",
+                self.region().cluster().host(),
+                puuid,
+                ids.len(),
+                count
+            );
+            if let Some(queue) = queue {
+                url.push_str(&format!("&queue={}", queue));
+            }
+            if let Some(since) = filter.since {
+                url.push_str(&format!("&startTime={}", since));
+            }
+            let res = self.riot.get(&url, Endpoint::MatchIds).await?;
+            let page: Vec<String> = match res.status {
+                404 => return Err(ProgramError::NoHistory),
+                200 => res.json()?,
+                _ => return Err(ProgramError::InvalidResponse),
+            };
+            let done = page.len() < count as usize;
+            ids.extend(page);
+            if done {
+                break;
+            }
+        }
+        Ok(ids)
+    }
+
+    // Fetches the details of the most recent matches, skipping any that could not be retrieved
+    async fn recent_matches(
+        &self,
+        puuid: &str,
+        filter: &HistoryFilter,
+    ) -> Result<Vec<MatchDataJSON>> {
+        let ids = self.match_ids(puuid, filter).await?;
+        Ok(self.matches(&ids).await)
+    }
+
+    // Fetches the details of each match, skipping any that could not be retrieved
+    async fn matches(&self, match_ids: &[String]) -> Vec<MatchDataJSON> {
+        // The client queues the requests so the burst stays under the rate limit
+        let games = match_ids
+            .iter()
+            .map(|id| self.match_data(id))
+            .collect::<Vec<_>>();
+        let result = join_all(games).await;
+        result.into_iter().filter_map(|m| m.ok()).collect()
+    }
+
+    // retrieves the match data for a given id
+    async fn match_data(&self, match_id: &str) -> Result<MatchDataJSON> {
+        let url = format!(
+            "{}/lol/match/v5/matches/{}",
+            self.region().cluster().host(),
+            match_id
+        );
+        let res = self.riot.get(&url, Endpoint::Match).await?;
+
+        match res.status {
+            200 => res.json(),
+            404 => Err(ProgramError::InvalidMatch),
+            _ => Err(ProgramError::BadResponse),
+        }
+    }
+
+    // Resolves a Riot ID to its puuid through Account-V1
+    async fn riot_account(&self, riot_id: &str) -> Result<RiotAccountJSON> {
+        let (game_name, tag_line) = parse_riot_id(riot_id, self.region());
+        if game_name.is_empty() || tag_line.is_empty() {
+            return Err(ProgramError::InvalidAccount);
+        }
+        let url = format!(
+            "{}/riot/account/v1/accounts/by-riot-id/{}/{}",
+            self.region().account_cluster().host(),
+            encode_segment(&game_name),
+            encode_segment(&tag_line)
+        );
+        let res = self.riot.get(&url, Endpoint::RiotAccount).await?;
+
+        match res.status {
+            200 => res.json(),
+            404 => Err(ProgramError::InvalidAccount),
+            _ => Err(ProgramError::BadResponse),
+        }
+    }
+}
+
+// Sets each player's premade group from their recent match ids, given in the order of the teams.
+// Players whose ids could not be fetched are treated as having no shared games.
+fn mark_premades(game: &mut Game, histories: &[Result<Vec<String>>]) {
+    let sides: Vec<Side> = game
+        .teams
+        .iter()
+        .flat_map(|t| t.players.iter().map(move |_| t.side))
+        .collect();
+    let histories: Vec<&[String]> = histories
+        .iter()
+        .map(|ids| ids.as_ref().map(|ids| ids.as_slice()).unwrap_or(&[]))
+        .collect();
+    let groups = premade_groups(&sides, &histories);
+    let players = game.teams.iter_mut().flat_map(|t| t.players.iter_mut());
+    for (player, group) in players.zip(groups) {
+        player.premade = group;
+    }
+    game.premades_checked = true;
+}
+
+// Numbers the groups of players on the same side who shared at least PREMADE_SHARED_GAMES of
+// their recent games. Players who queued alone get None.
+fn premade_groups(sides: &[Side], histories: &[&[String]]) -> Vec<Option<u8>> {
+    // Every player starts in their own group and groups are merged through shared games
+    let mut leader: Vec<usize> = (0..sides.len()).collect();
+    fn find(leader: &mut [usize], i: usize) -> usize {
+        let mut root = i;
+        while leader[root] != root {
+            root = leader[root];
+        }
+        leader[i] = root;
+        root
+    }
+    for i in 0..sides.len() {
+        for j in i + 1..sides.len() {
+            let shared = histories[i]
+                .iter()
+                .filter(|id| histories[j].contains(id))
+                .count();
+            if sides[i] == sides[j] && shared >= PREMADE_SHARED_GAMES {
+                let (a, b) = (find(&mut leader, i), find(&mut leader, j));
+                leader[b] = a;
+            }
+        }
+    }
+
+    let roots: Vec<usize> = (0..sides.len()).map(|i| find(&mut leader, i)).collect();
+    let mut numbered: Vec<usize> = Vec::new();
+    roots
+        .iter()
+        .map(|root| {
+            if roots.iter().filter(|r| *r == root).count() < 2 {
+                return None;
+            }
+            let number = match numbered.iter().position(|r| r == root) {
+                Some(n) => n,
+                None => {
+                    numbered.push(*root);
+                    numbered.len() - 1
+                }
+            };
+            Some(number as u8 + 1)
+        })
+        .collect()
+}
+
+// Divides without producing NaN or infinity for games that ended before anything happened
+fn ratio(value: f32, total: f32) -> f32 {
+    match total > 0.0 {
+        true => value / total,
+        false => 0.0,
+    }
+}
+
+// The number after the platform prefix, e.g. 4961234567 for "NA1_4961234567". It grows with every game.
+fn match_number(id: &str) -> u64 {
+    id.rsplit('_')
+        .next()
+        .and_then(|n| n.parse().ok())
+        .unwrap_or(0)
+}
+
+// Returns the most played role
+fn most_played_role(matches: &[MatchDataJSON], puuid: &str) -> String {
+    let mut map: HashMap<String, i8> = HashMap::new();
+    for game in matches {
+        if let Some(p) = game.participant(puuid) {
+            // Modes without lanes such as ARAM report an empty position
+            if !p.teamPosition.is_empty() {
+                *map.entry(determine_role(&p.teamPosition)).or_insert(0) += 1;
+            }
+        }
+    }
+
+    let mut max = ("NONE", -1);
+    for (key, value) in map.iter() {
+        if *value > max.1 {
+            max = (key, *value);
+        }
+    }
+    String::from(max.0)
+}
+
+fn determine_role(position: &str) -> String {
+    match position {
+        "BOTTOM" => "ADC".to_string(),
+        "UTILITY" => "SUPPORT".to_string(),
+        "MIDDLE" => "MID".to_string(),
+        "" => "NONE".to_string(),
+        _ => position.to_string(),
+    }
+}
+
+// Splits a Riot ID into its game name and tag line. Names without a tag use the region's default.
+fn parse_riot_id(riot_id: &str, region: Region) -> (String, String) {
+    match riot_id.rfind('#') {
+        Some(i) => (
+            riot_id[..i].trim().to_string(),
+            riot_id[i + 1..].trim().to_string(),
+        ),
+        None => (riot_id.trim().to_string(), region.default_tag().to_string()),
+    }
+}
+
+// Percent encodes a value so it can be used as a single url path segment
+fn encode_segment(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(ids: &[&str]) -> Vec<String> {
+        ids.iter().map(|id| id.to_string()).collect()
+    }
+
+    fn groups(sides: &[Side], histories: &[Vec<String>]) -> Vec<Option<u8>> {
+        let histories: Vec<&[String]> = histories.iter().map(|h| h.as_slice()).collect();
+        premade_groups(sides, &histories)
+    }
+
+    #[test]
+    fn separate_duos_get_their_own_groups() {
+        let sides = [Side::Blue; 5];
+        let histories = [
+            ids(&["NA1_1", "NA1_2"]),
+            ids(&["NA1_1", "NA1_2", "NA1_3"]),
+            ids(&["NA1_4", "NA1_5"]),
+            ids(&["NA1_6"]),
+            ids(&["NA1_4", "NA1_5", "NA1_6"]),
+        ];
+        assert_eq!(
+            groups(&sides, &histories),
+            vec![Some(1), Some(1), Some(2), None, Some(2)]
+        );
+    }
+
+    #[test]
+    fn opponents_are_never_grouped() {
+        let sides = [Side::Blue, Side::Red];
+        let histories = [ids(&["NA1_1", "NA1_2"]), ids(&["NA1_1", "NA1_2"])];
+        assert_eq!(groups(&sides, &histories), vec![None, None]);
+    }
+
+    #[test]
+    fn groups_are_transitive() {
+        // The first and last player share only one game but both queued with the middle one
+        let sides = [Side::Red; 3];
+        let histories = [
+            ids(&["NA1_1", "NA1_2", "NA1_9"]),
+            ids(&["NA1_1", "NA1_2", "NA1_3", "NA1_4"]),
+            ids(&["NA1_3", "NA1_4", "NA1_9"]),
+        ];
+        assert_eq!(groups(&sides, &histories), vec![Some(1), Some(1), Some(1)]);
+    }
+
+    #[test]
+    fn one_shared_game_is_not_a_premade() {
+        let sides = [Side::Blue; 2];
+        let histories = [ids(&["NA1_1", "NA1_2"]), ids(&["NA1_1", "NA1_3"])];
+        assert_eq!(groups(&sides, &histories), vec![None, None]);
+    }
+
+    #[test]
+    fn missing_histories_play_alone() {
+        // Bots and players whose match ids could not be fetched have no games to share
+        let sides = [Side::Blue; 3];
+        let histories = [
+            ids(&["NA1_1", "NA1_2"]),
+            Vec::new(),
+            ids(&["NA1_1", "NA1_2"]),
+        ];
+        assert_eq!(groups(&sides, &histories), vec![Some(1), None, Some(1)]);
+        assert_eq!(
+            groups(&sides, &[Vec::new(), Vec::new(), Vec::new()]),
+            vec![None, None, None]
+        );
+    }
+}
@@ -0,0 +1,96 @@
+/// This file contains the on-disk cache for Riot API responses
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const CACHE_DIR: &str = "lolookup";
+const RESPONSE_DIR: &str = "responses";
+
+// A cached response body. The url is kept to tell apart the rare urls that hash the same.
+#[derive(Serialize, Deserialize)]
+struct Entry {
+    url: String,
+    stored: u64,
+    body: String,
+}
+
+pub struct Cache {
+    dir: Option<PathBuf>,
+}
+
+impl Cache {
+    pub fn new(enabled: bool) -> Self {
+        let dir = match enabled {
+            true => cache_dir().map(|dir| dir.join(RESPONSE_DIR)),
+            false => None,
+        };
+        Cache { dir }
+    }
+
+    // Returns the cached body for `url` unless it is older than `ttl`. A `ttl` of None never expires.
+    pub fn get(&self, url: &str, ttl: Option<Duration>) -> Option<String> {
+        let path = self.path(url)?;
+        let data = fs::read_to_string(path).ok()?;
+        let entry: Entry = serde_json::from_str(&data).ok()?;
+        if entry.url != url {
+            return None;
+        }
+        if let Some(ttl) = ttl {
+            if now().saturating_sub(entry.stored) >= ttl.as_secs() {
+                return None;
+            }
+        }
+        Some(entry.body)
+    }
+
+    // Caching is best effort, a response that cannot be written is simply fetched again next time
+    pub fn put(&self, url: &str, body: &str) {
+        let path = match self.path(url) {
+            Some(path) => path,
+            None => return,
+        };
+        let entry = Entry {
+            url: url.to_string(),
+            stored: now(),
+            body: body.to_string(),
+        };
+        if let (Some(dir), Ok(data)) = (path.parent(), serde_json::to_string(&entry)) {
+            if fs::create_dir_all(dir).is_ok() {
+                let _ = fs::write(path, data);
+            }
+        }
+    }
+
+    fn path(&self, url: &str) -> Option<PathBuf> {
+        self.dir
+            .as_ref()
+            .map(|dir| dir.join(format!("{:016x}.json", fnv1a(url))))
+    }
+}
+
+pub fn cache_dir() -> Option<PathBuf> {
+    dirs::cache_dir().map(|dir| dir.join(CACHE_DIR))
+}
+
+// Removes every cached response
+pub fn clear() -> std::io::Result<()> {
+    match cache_dir().map(|dir| dir.join(RESPONSE_DIR)) {
+        Some(dir) if dir.exists() => fs::remove_dir_all(dir),
+        _ => Ok(()),
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+// A hash that stays the same between builds so cache file names do not change
+fn fnv1a(value: &str) -> u64 {
+    value.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, b| {
+        (hash ^ b as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
@@ -1,7 +1,14 @@
 /// This file contains all data for champions
+use crate::ddragon;
+use crate::error::ProgramError;
+use serde::Deserialize;
 use std::collections::HashMap;
 
-#[macro_export]
+pub const DEFAULT_CHAMP: &str = "Unknown Champ";
+const CHAMPION_FILE: &str = "champion.json";
+// The version shown for the names compiled into the binary
+const BUNDLED_VERSION: &str = "bundled";
+
 macro_rules! hashmap {
     (string $($key:expr => $val:expr),+ $(,)?) => {
         {
@@ -19,41 +26,145 @@ macro_rules! hashmap {
     };
 }
 
-pub fn rank_map() -> HashMap<String, u8> {
-    hashmap!(
-        "N/A".to_string() => 0,
-        "I_IV".to_string() => 1,
-        "I_III".to_string() => 2,
-        "I_II".to_string() => 3,
-        "I_I".to_string() => 4,
-        "B_IV".to_string() => 5,
-        "B_III".to_string() => 6,
-        "B_II".to_string() => 7,
-        "B_I".to_string() => 8,
-        "S_IV".to_string() => 9,
-        "S_III".to_string() => 10,
-        "S_II".to_string() => 11,
-        "S_I".to_string()  => 12,
-        "G_IV".to_string() => 13,
-        "G_III".to_string() => 14,
-        "G_II".to_string() => 15,
-        "G_I".to_string()  => 16,
-        "P_IV".to_string() => 17,
-        "P_III".to_string() => 18,
-        "P_II".to_string() => 19,
-        "P_I".to_string()  => 20,
-        "D_IV".to_string() => 21,
-        "D_III".to_string() => 22,
-        "D_II".to_string() => 23,
-        "D_I".to_string() => 24,
-        "MASTER".to_string() => 25,
-        "GRANDMASTER".to_string() => 26,
-        "CHALLENGER".to_string() => 27,
-    )
+#[derive(Deserialize, Debug)]
+struct ChampionFileJSON {
+    version: String,
+    data: HashMap<String, ChampionJSON>,
+}
+
+#[derive(Deserialize, Debug)]
+struct ChampionJSON {
+    id: String,
+    key: String,
+    name: String,
+    title: String,
+    tags: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Champion {
+    pub id: String,
+    pub key: u16,
+    pub name: String,
+    pub title: String,
+    pub tags: Vec<String>,
+}
+
+// Champion metadata by numeric key, the id the Riot API uses
+pub struct Champions {
+    pub version: String,
+    by_key: HashMap<u16, Champion>,
+}
+
+impl Champions {
+    // Uses the saved Data Dragon file, downloading it on the first run. Falls back to the bundled
+    // names when Data Dragon cannot be reached.
+    pub async fn load() -> Self {
+        if let Some(file) = ddragon::read_file::<ChampionFileJSON>(CHAMPION_FILE) {
+            return Self::from_file(file);
+        }
+        match Self::refresh(None).await {
+            Ok(champions) => champions,
+            Err(_) => Self::fallback(),
+        }
+    }
+
+    // Downloads the champion data of `version`, or of the latest patch when no version is given
+    pub async fn refresh(version: Option<&str>) -> Result<Self, ProgramError> {
+        let file: ChampionFileJSON = ddragon::fetch(CHAMPION_FILE, version).await?;
+        Ok(Self::from_file(file))
+    }
+
+    fn from_file(file: ChampionFileJSON) -> Self {
+        let by_key = file
+            .data
+            .into_values()
+            .filter_map(|c| {
+                let key = c.key.parse().ok()?;
+                Some((
+                    key,
+                    Champion {
+                        id: c.id,
+                        key,
+                        name: c.name,
+                        title: c.title,
+                        tags: c.tags,
+                    },
+                ))
+            })
+            .collect();
+        Champions {
+            version: file.version,
+            by_key,
+        }
+    }
+
+    fn fallback() -> Self {
+        let by_key = fallback_names()
+            .into_iter()
+            .map(|(key, name)| {
+                let champion = Champion {
+                    id: name.chars().filter(|c| c.is_alphanumeric()).collect(),
+                    key,
+                    name,
+                    title: String::new(),
+                    tags: Vec::new(),
+                };
+                (key, champion)
+            })
+            .collect();
+        Champions {
+            version: String::from(BUNDLED_VERSION),
+            by_key,
+        }
+    }
+
+    // The Data Dragon patch the names come from, None for the bundled names
+    pub fn patch(&self) -> Option<&str> {
+        match self.version == BUNDLED_VERSION {
+            true => None,
+            false => Some(&self.version),
+        }
+    }
+
+    pub fn get(&self, key: u16) -> Option<&Champion> {
+        self.by_key.get(&key)
+    }
+
+    pub fn name(&self, key: u16) -> &str {
+        self.get(key).map(|c| &c.name[..]).unwrap_or(DEFAULT_CHAMP)
+    }
+
+    // Finds a champion by key, id or name. Case, spaces and punctuation are ignored so "kaisa"
+    // and "Kai'Sa" both match.
+    pub fn find(&self, query: &str) -> Option<&Champion> {
+        if let Ok(key) = query.parse::<u16>() {
+            return self.get(key);
+        }
+        let normalize = |s: &str| -> String {
+            s.chars()
+                .filter(|c| c.is_alphanumeric())
+                .flat_map(char::to_lowercase)
+                .collect()
+        };
+        let query = normalize(query);
+        self.by_key
+            .values()
+            .find(|c| normalize(&c.name) == query || normalize(&c.id) == query)
+    }
+
+    pub fn len(&self) -> usize {
+        self.by_key.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_key.is_empty()
+    }
 }
 
-pub fn champion_map() -> HashMap<u16, String> {
-    let map = hashmap!( string
+// Champion names bundled with the binary, used when Data Dragon cannot be reached
+fn fallback_names() -> HashMap<u16, String> {
+    hashmap!( string
         1 => "Annie",
         2 => "Olaf",
         3 => "Galio",
@@ -65,15 +176,15 @@ pub fn champion_map() -> HashMap<u16, String> {
         9 => "Fiddlesticks",
         10 => "Kayle",
         11 => "Master Yi",
-        12 => "Alister",
+        12 => "Alistar",
         13 => "Ryze",
         14 => "Sion",
-        15 => "Siver",
+        15 => "Sivir",
         16 => "Soraka",
         17 => "Teemo",
         18 => "Tristana",
         19 => "Warwick",
-        20 => "Nunu",
+        20 => "Nunu & Willump",
         21 => "Miss Fortune",
         22 => "Ashe",
         23 => "Tryndamere",
@@ -113,7 +224,7 @@ pub fn champion_map() -> HashMap<u16, String> {
         61 => "Orianna",
         62 => "Wukong",
         63 => "Brand",
-        64 => "LeeSin",
+        64 => "Lee Sin",
         67 => "Vayne",
         68 => "Rumble",
         69 => "Cassiopeia",
@@ -141,19 +252,19 @@ pub fn champion_map() -> HashMap<u16, String> {
         101 => "Xerath",
         102 => "Shyvana",
         103 => "Ahri",
-        104 => "Granves",
+        104 => "Graves",
         105 => "Fizz",
         106 => "Volibear",
         107 => "Rengar",
         110 => "Varus",
         111 => "Nautilus",
-        112 => "Victor",
+        112 => "Viktor",
         113 => "Sejuani",
         114 => "Fiora",
         115 => "Ziggs",
         117 => "Lulu",
         119 => "Draven",
-        120 => "Hecrim",
+        120 => "Hecarim",
         121 => "Kha'Zix",
         122 => "Darius",
         126 => "Jayce",
@@ -165,18 +276,24 @@ pub fn champion_map() -> HashMap<u16, String> {
         141 => "Kayn",
         142 => "Zoe",
         143 => "Zyra",
-        145 => "Kai'sa",
+        145 => "Kai'Sa",
+        147 => "Seraphine",
         150 => "Gnar",
         154 => "Zac",
-        157 => "Yasauo",
+        157 => "Yasuo",
         161 => "Vel'Koz",
         163 => "Taliyah",
         164 => "Camille",
+        166 => "Akshan",
+        200 => "Bel'Veth",
         201 => "Braum",
         202 => "Jhin",
         203 => "Kindred",
+        221 => "Zeri",
         222 => "Jinx",
         223 => "Tahm Kench",
+        233 => "Briar",
+        234 => "Viego",
         235 => "Senna",
         236 => "Lucian",
         238 => "Zed",
@@ -188,9 +305,10 @@ pub fn champion_map() -> HashMap<u16, String> {
         267 => "Nami",
         268 => "Azir",
         350 => "Yuumi",
+        360 => "Samira",
         412 => "Thresh",
-        420 => "IIlaoi",
-        421 => "Rek Sai",
+        420 => "Illaoi",
+        421 => "Rek'Sai",
         427 => "Ivern",
         429 => "Kalista",
         432 => "Bard",
@@ -200,10 +318,22 @@ pub fn champion_map() -> HashMap<u16, String> {
         517 => "Sylas",
         518 => "Neeko",
         523 => "Aphelios",
+        526 => "Rell",
         555 => "Pyke",
+        711 => "Vex",
         777 => "Yone",
-        875 => "Pyke",
+        799 => "Ambessa",
+        800 => "Mel",
+        875 => "Sett",
         876 => "Lillia",
-    );
-    map
+        887 => "Gwen",
+        888 => "Renata Glasc",
+        893 => "Aurora",
+        895 => "Nilah",
+        897 => "K'Sante",
+        901 => "Smolder",
+        902 => "Milio",
+        910 => "Hwei",
+        950 => "Naafiri",
+    )
 }
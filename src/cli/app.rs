@@ -0,0 +1,313 @@
+/// This file contains the command line definition: subcommands, options and their validation
+use chrono::NaiveDate;
+use clap::{crate_version, App, AppSettings, Arg, ArgGroup, SubCommand};
+use lolookup::queue;
+use lolookup::{ProgramError, Region};
+
+pub const BIN_NAME: &str = "lol";
+// Live games are cached for 30 seconds, checking much more often only waits on the rate limit
+pub const MIN_WATCH_INTERVAL: u64 = 5;
+
+pub fn build_app() -> App<'static, 'static> {
+    App::new(BIN_NAME)
+        .version(crate_version!())
+        .about("Looks up League of Legends players, live games and match history")
+        .after_help(
+            "Players are looked up by Riot ID. Without a #tag the region's default tag is used.\n\
+             Names with spaces can be quoted or typed as separate words: lol lookup Hide on bush#KR1",
+        )
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        .setting(AppSettings::VersionlessSubcommands)
+        .arg(
+            Arg::with_name("region")
+                .long("region")
+                .value_name("REGION")
+                .help("Region to query, e.g. euw1, kr, br1")
+                .takes_value(true)
+                .global(true)
+                .validator(|r| match r.parse::<Region>() {
+                    Ok(_) => Ok(()),
+                    Err(_) => Err(ProgramError::InvalidRegion(r).to_string()),
+                }),
+        )
+        .arg(
+            Arg::with_name("api-key")
+                .long("api-key")
+                .value_name("API_KEY")
+                .help("Riot API key, overrides RIOT_API_KEY and the config file")
+                .takes_value(true)
+                .global(true),
+        )
+        .arg(
+            Arg::with_name("retries")
+                .long("retries")
+                .value_name("N")
+                .help("Retries after a server or network error [default: 3]")
+                .takes_value(true)
+                .global(true)
+                .validator(|v| is_number::<u32>("--retries", v)),
+        )
+        .arg(
+            Arg::with_name("timeout")
+                .long("timeout")
+                .value_name("SECONDS")
+                .help("Request timeout [default: 10]")
+                .takes_value(true)
+                .global(true)
+                .validator(|v| is_number::<u64>("--timeout", v)),
+        )
+        .arg(
+            Arg::with_name("no-cache")
+                .long("no-cache")
+                .help("Always fetch fresh data from Riot")
+                .global(true),
+        )
+        .arg(
+            Arg::with_name("format")
+                .long("format")
+                .value_name("FORMAT")
+                .help("Output format")
+                .takes_value(true)
+                .possible_values(&["table", "json", "ndjson"])
+                .case_insensitive(true)
+                .default_value("table")
+                .global(true),
+        )
+        .subcommand(
+            SubCommand::with_name("lookup")
+                .about("Shows account statistics")
+                .arg(riot_id_arg()),
+        )
+        .subcommand(
+            SubCommand::with_name("game")
+                .about("Shows the players in the current game")
+                .arg(riot_id_arg())
+                .arg(
+                    Arg::with_name("champion-stats")
+                        .long("champion-stats")
+                        .short("c")
+                        .help("Adds each player's recent games, win rate and mastery on their champion"),
+                )
+                .arg(
+                    Arg::with_name("premades")
+                        .long("premades")
+                        .short("p")
+                        .help("Marks the players who queued together from the games they shared"),
+                )
+                .group(
+                    ArgGroup::with_name("live-history")
+                        .args(&["champion-stats", "premades"])
+                        .multiple(true),
+                )
+                .arg(
+                    Arg::with_name("games")
+                        .long("games")
+                        .value_name("N")
+                        .help("Recent games per player read by --champion-stats and --premades [default: 10]")
+                        .takes_value(true)
+                        .requires("live-history")
+                        .validator(|v| match v.parse::<u16>() {
+                            Ok(n) if n > 0 => Ok(()),
+                            _ => Err(format!("--games expects a positive number, got '{}'", v)),
+                        }),
+                )
+                .arg(
+                    Arg::with_name("watch")
+                        .long("watch")
+                        .short("w")
+                        .help("Waits for the player to enter a game instead of failing"),
+                )
+                .arg(
+                    Arg::with_name("follow")
+                        .long("follow")
+                        .short("f")
+                        .help("Keeps refreshing the table until the game ends, then exits with status 3")
+                        .requires("watch"),
+                )
+                .arg(
+                    Arg::with_name("interval")
+                        .long("interval")
+                        .value_name("SECONDS")
+                        .help("Seconds between checks while watching [default: 20]")
+                        .takes_value(true)
+                        .requires("watch")
+                        .validator(|v| match v.parse::<u64>() {
+                            Ok(n) if n >= MIN_WATCH_INTERVAL => Ok(()),
+                            _ => Err(format!(
+                                "--interval expects a number of seconds of at least {}, got '{}'",
+                                MIN_WATCH_INTERVAL, v
+                            )),
+                        }),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("history")
+                .about("Shows the match history")
+                .arg(riot_id_arg())
+                .args(&history_filter_args())
+                .arg(
+                    Arg::with_name("expand")
+                        .long("expand")
+                        .short("e")
+                        .value_name("ROW")
+                        .help("Also shows the scoreboard of the game in this row, counting from 1")
+                        .takes_value(true)
+                        .validator(|v| is_number::<usize>("--expand", v)),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("champions")
+                .about("Shows win rate, KDA and CS per champion over the recent games")
+                .arg(riot_id_arg())
+                .args(&history_filter_args())
+                .arg(
+                    Arg::with_name("mastery")
+                        .long("mastery")
+                        .short("m")
+                        .help("Adds each champion's mastery level and points"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("mastery")
+                .about("Shows the champions with the most mastery points")
+                .arg(riot_id_arg())
+                .arg(
+                    Arg::with_name("top")
+                        .long("top")
+                        .short("t")
+                        .value_name("N")
+                        .help("Number of champions to show [default: 10]")
+                        .takes_value(true)
+                        .validator(|v| is_number::<usize>("--top", v)),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("match")
+                .about("Shows the scoreboard of a finished match")
+                .arg(
+                    Arg::with_name("id")
+                        .value_name("MATCH_ID")
+                        .help("Match id, e.g. NA1_4961234567. The prefix defaults to the region")
+                        .required(true),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("region")
+                .about("Shows or sets the default region")
+                .arg(Arg::with_name("default").value_name("REGION")),
+        )
+        .subcommand(
+            SubCommand::with_name("key")
+                .about("Saves the Riot API key to the config file")
+                .arg(Arg::with_name("key").value_name("API_KEY").required(true)),
+        )
+        .subcommand(
+            SubCommand::with_name("data")
+                .about("Shows the champion data downloaded from Data Dragon")
+                .subcommand(
+                    SubCommand::with_name("refresh")
+                        .about("Downloads the static data of the latest or given patch")
+                        .arg(Arg::with_name("patch").value_name("PATCH")),
+                )
+                .subcommand(
+                    SubCommand::with_name("queues")
+                        .about("Lists the queue ids and game modes used by Riot"),
+                )
+                .subcommand(
+                    SubCommand::with_name("champion")
+                        .about("Shows a champion's key, title and tags")
+                        .arg(
+                            Arg::with_name("name")
+                                .value_name("NAME")
+                                .required(true)
+                                .multiple(true),
+                        ),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("cache")
+                .about("Manages the response cache")
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(SubCommand::with_name("clear").about("Removes every cached response")),
+        )
+        .subcommand(
+            SubCommand::with_name("completions")
+                .about("Prints a shell completion script")
+                .arg(
+                    Arg::with_name("shell")
+                        .value_name("SHELL")
+                        .required(true)
+                        .possible_values(&["bash", "zsh", "fish", "powershell", "elvish"]),
+                ),
+        )
+}
+
+// A Riot ID given as one quoted argument or as several words, e.g. `Hide on bush#KR1`
+fn riot_id_arg() -> Arg<'static, 'static> {
+    Arg::with_name("name")
+        .value_name("NAME#TAG")
+        .help("Riot ID of the player")
+        .required(true)
+        .multiple(true)
+}
+
+// The options that pick which recent games are fetched
+fn history_filter_args() -> Vec<Arg<'static, 'static>> {
+    vec![
+        Arg::with_name("count")
+            .long("count")
+            .short("n")
+            .value_name("N")
+            .help("Number of games to fetch [default: 20]")
+            .takes_value(true)
+            .validator(|v| match v.parse::<u16>() {
+                Ok(n) if n > 0 => Ok(()),
+                _ => Err(format!("--count expects a positive number, got '{}'", v)),
+            }),
+        Arg::with_name("queue")
+            .long("queue")
+            .value_name("QUEUE")
+            .help("Only games of these queues, e.g. ranked-solo,aram. Takes queue names or ids")
+            .takes_value(true)
+            .multiple(true)
+            .use_delimiter(true)
+            .number_of_values(1)
+            .validator(|v| match queue::queue_id(&v) {
+                Some(_) => Ok(()),
+                None => Err(format!(
+                    "Unknown queue '{}'. Valid queues: {}",
+                    v,
+                    queue::QUEUES
+                        .iter()
+                        .map(|q| q.slug)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )),
+            }),
+        Arg::with_name("since")
+            .long("since")
+            .value_name("YYYY-MM-DD")
+            .help("Only games played on or after this date (UTC)")
+            .takes_value(true)
+            .validator(|v| match parse_date(&v) {
+                Some(_) => Ok(()),
+                None => Err(format!(
+                    "--since expects a date like 2026-09-01, got '{}'",
+                    v
+                )),
+            }),
+    ]
+}
+
+fn is_number<T: std::str::FromStr>(flag: &str, value: String) -> Result<(), String> {
+    match value.parse::<T>() {
+        Ok(_) => Ok(()),
+        Err(_) => Err(format!("{} expects a number, got '{}'", flag, value)),
+    }
+}
+
+// Returns the unix timestamp of midnight UTC on a YYYY-MM-DD date
+pub fn parse_date(value: &str) -> Option<i64> {
+    let date = NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d").ok()?;
+    Some(date.and_hms_opt(0, 0, 0)?.and_utc().timestamp())
+}
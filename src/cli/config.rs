@@ -0,0 +1,50 @@
+/// This file contains the user settings that persist between runs
+use lolookup::ProgramError;
+use lolookup::Region;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+const CONFIG_DIR: &str = "lolookup";
+const CONFIG_FILE: &str = "config.json";
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Config {
+    #[serde(default)]
+    pub region: Option<Region>,
+    #[serde(default)]
+    pub api_key: Option<String>,
+    // Number of times a request is retried after a server error or network failure
+    #[serde(default)]
+    pub retries: Option<u32>,
+    // Request timeout in seconds
+    #[serde(default)]
+    pub timeout: Option<u64>,
+}
+
+impl Config {
+    // Loads the config file, falling back to the defaults when it has not been written yet
+    pub fn load() -> Result<Self, ProgramError> {
+        let path = match config_path() {
+            Some(path) => path,
+            None => return Ok(Config::default()),
+        };
+        match fs::read_to_string(&path) {
+            Ok(data) => serde_json::from_str(&data).map_err(|_| ProgramError::ConfigError),
+            Err(_) => Ok(Config::default()),
+        }
+    }
+
+    pub fn save(&self) -> Result<(), ProgramError> {
+        let path = config_path().ok_or(ProgramError::ConfigError)?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|_| ProgramError::ConfigError)?;
+        }
+        let data = serde_json::to_string_pretty(self).map_err(|_| ProgramError::ConfigError)?;
+        fs::write(&path, data).map_err(|_| ProgramError::ConfigError)
+    }
+}
+
+pub fn config_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join(CONFIG_DIR).join(CONFIG_FILE))
+}
@@ -0,0 +1,602 @@
+/// This file contains the console tables printed for each lookup
+use super::output::Output;
+use chrono::{DateTime, Utc};
+use console::{Style, StyledObject};
+use lolookup::rank::{RankOrdinal, RankedQueue};
+use lolookup::{
+    ChampionPool, ChampionStats, Game, Mastery, MatchDetails, Participant, PlayerMasteries,
+    PlayerStats, Rank, Side, UserAccount, UserGames, UserMatch,
+};
+use serde_json::{json, Value};
+
+const ACC_COLS: [&str; 5] = ["Queue", "Rank", "W/L", "LP", "Hot Streak"];
+const GAME_COLS: [&str; 9] = [
+    "Username",
+    "Party",
+    "Rank",
+    "LP",
+    "W/L",
+    "Champion",
+    "Spells",
+    "Runes",
+    "Hot Streak",
+];
+const MATCH_HISTORY_COLS: [&str; 11] = [
+    "#", "Role", "Mode", "Champion", "K/D/A", "KDA", "CS/m", "KP", "Dmg", "Time", "Result",
+];
+const CHAMPION_COLS: [&str; 8] = [
+    "Champion", "Games", "W/L", "Win%", "K/D/A", "KDA", "CS/m", "Mastery",
+];
+const MASTERY_COLS: [&str; 5] = ["Champion", "Level", "Points", "Last Played", "Chest"];
+const SCOREBOARD_COLS: [&str; 9] = [
+    "Player", "Champion", "Lvl", "K/D/A", "CS", "Gold", "Damage", "Vision", "Runes",
+];
+// Longer queue names, e.g. from Riot's queue list, are cut to keep the history rows aligned
+const MODE_WIDTH: usize = 20;
+const FIRE: &str = "🔥";
+const COLD: &str = "🧊";
+
+macro_rules! gameHeader {
+    ($team:expr, $i:ident) => {
+        println!("{:=^134}", $i.apply_to($team));
+        println!(
+            "{0: ^17} | {1: ^5} | {2: ^6} | {3: ^6} | {4: ^6} | {5: ^14} | {6: ^18} | {7: ^28} | {8: ^10}",
+            GAME_COLS[0],
+            GAME_COLS[1],
+            GAME_COLS[2],
+            GAME_COLS[3],
+            GAME_COLS[4],
+            GAME_COLS[5],
+            GAME_COLS[6],
+            GAME_COLS[7],
+            GAME_COLS[8]
+        );
+        println!(
+            "{:-<18}+{:-<7}+{:-<8}+{:-<8}+{:-<8}+{:-<16}+{:-<20}+{:-<30}+{:-<12}",
+            "-", "-", "-", "-", "-", "-", "-", "-", "-"
+        );
+    };
+}
+
+macro_rules! emoji {
+    ($e:expr, $o:expr) => {{
+        match utf8_supported() {
+            true => $e,
+            false => $o,
+        }
+    }};
+}
+
+impl Output for UserGames {
+    fn display_console(&self) {
+        let yellow: Style = Style::new().yellow();
+        let label = format!(" {} Match History ", &self.username);
+        println!("{:=^101}", yellow.apply_to(&label));
+        println!("Last {} games stats:", self.games.len());
+        println!("Total wins: {}", self.wins());
+        println!("Total losses: {}", self.losses());
+        match self.win_rate() {
+            Some(rate) => println!("W/L Ratio: {:.2}%", rate),
+            None => println!("W/L Ratio: N/A"),
+        }
+        if let Some(kda) = self.average(|g| g.kda_ratio()) {
+            // Every average is Some once one of them is, the same games are counted
+            let avg = |stat: fn(&UserMatch) -> f32| self.average(stat).unwrap_or(0.0);
+            println!(
+                "Avg K/D/A: {:.1}/{:.1}/{:.1} ({:.2} KDA)",
+                avg(|g| g.kills as f32),
+                avg(|g| g.deaths as f32),
+                avg(|g| g.assists as f32),
+                kda
+            );
+            println!(
+                "Avg CS/min: {:.1} | Kill participation: {:.0}% | Damage share: {:.0}%",
+                avg(|g| g.cs_per_min),
+                avg(|g| g.kill_participation),
+                avg(|g| g.damage_share)
+            );
+            println!(
+                "Avg game length: {}",
+                format_duration(avg(|g| g.duration as f32) as u32)
+            );
+        }
+        println!(
+            "{0: >3} | {1: ^7} | {2: ^20} | {3: ^14} | {4: ^8} | {5: ^4} | {6: ^4} | {7: ^4} | {8: ^4} | {9: ^5} | {10: ^6}",
+            MATCH_HISTORY_COLS[0],
+            MATCH_HISTORY_COLS[1],
+            MATCH_HISTORY_COLS[2],
+            MATCH_HISTORY_COLS[3],
+            MATCH_HISTORY_COLS[4],
+            MATCH_HISTORY_COLS[5],
+            MATCH_HISTORY_COLS[6],
+            MATCH_HISTORY_COLS[7],
+            MATCH_HISTORY_COLS[8],
+            MATCH_HISTORY_COLS[9],
+            MATCH_HISTORY_COLS[10]
+        );
+        println!(
+            "{:-<4}+{:-<9}+{:-<22}+{:-<16}+{:-<10}+{:-<6}+{:-<6}+{:-<6}+{:-<6}+{:-<7}+{:-<7}",
+            "-", "-", "-", "-", "-", "-", "-", "-", "-", "-", "-"
+        );
+        for (i, game) in self.games.iter().enumerate() {
+            display_match_row(i + 1, game);
+        }
+    }
+
+    fn records(&self) -> Vec<Value> {
+        self.games
+            .iter()
+            .filter_map(|game| {
+                let mut record = serde_json::to_value(game).ok()?;
+                record["username"] = json!(self.username);
+                Some(record)
+            })
+            .collect()
+    }
+}
+
+fn display_match_row(row: usize, game: &UserMatch) {
+    println!(
+        "{0: >3} | {1: ^7} | {2: ^20} | {3: ^14} | {4: ^8} | {5: ^4.1} | {6: ^4.1} | {7: >3.0}% | {8: >3.0}% | {9: ^5} | {10: ^6}",
+        row,
+        game.role,
+        fit(&game.queue, MODE_WIDTH),
+        game.champion,
+        format!("{}/{}/{}", game.kills, game.deaths, game.assists),
+        game.kda_ratio(),
+        game.cs_per_min,
+        game.kill_participation,
+        game.damage_share,
+        format_duration(game.duration),
+        game.get_outcome()
+    );
+}
+
+impl Output for UserAccount {
+    fn display_console(&self) {
+        let yellow: Style = Style::new().yellow();
+        println!("{:=^58}", yellow.apply_to(&self.account.name));
+        println!(
+            "Level: {} | Top Role: {}",
+            self.account.summoner_level, self.top_role
+        );
+        println!(
+            "{0: ^17} | {1: ^6} | {2: ^6} | {3: ^6} | {4: ^10}",
+            ACC_COLS[0], ACC_COLS[1], ACC_COLS[2], ACC_COLS[3], ACC_COLS[4]
+        );
+        println!(
+            "{:-<18}+{:-<8}+{:-<8}+{:-<8}+{:-<12}",
+            "-", "-", "-", "-", "-"
+        );
+        if self.ranks.is_empty() {
+            display_rank_row(&Rank::unranked(RankedQueue::SoloDuo));
+        }
+        for rank in &self.ranks {
+            display_rank_row(rank);
+        }
+        if !self.top_masteries.is_empty() {
+            let top: Vec<String> = self
+                .top_masteries
+                .iter()
+                .map(|m| {
+                    format!(
+                        "{} (Lvl {}, {} pts)",
+                        m.champion,
+                        m.level,
+                        format_points(m.points)
+                    )
+                })
+                .collect();
+            println!("Top mastery: {}", top.join(", "));
+        }
+    }
+}
+
+impl Output for PlayerMasteries {
+    fn display_console(&self) {
+        let yellow: Style = Style::new().yellow();
+        let label = format!(" {} Champion Mastery ", &self.username);
+        println!("{:=^66}", yellow.apply_to(&label));
+        println!(
+            "{0: <14} | {1: ^5} | {2: ^11} | {3: ^16} | {4: ^5}",
+            MASTERY_COLS[0], MASTERY_COLS[1], MASTERY_COLS[2], MASTERY_COLS[3], MASTERY_COLS[4]
+        );
+        println!(
+            "{:-<15}+{:-<7}+{:-<13}+{:-<18}+{:-<7}",
+            "-", "-", "-", "-", "-"
+        );
+        for m in &self.masteries {
+            println!(
+                "{0: <14} | {1: ^5} | {2: >11} | {3: ^16} | {4: ^5}",
+                m.champion,
+                m.level,
+                format_points(m.points),
+                format_ago(m.last_played),
+                match m.chest_granted {
+                    Some(true) => "Yes",
+                    Some(false) => "No",
+                    None => "-",
+                }
+            );
+        }
+    }
+
+    fn records(&self) -> Vec<Value> {
+        self.masteries
+            .iter()
+            .filter_map(|m| {
+                let mut record = serde_json::to_value(m).ok()?;
+                record["username"] = json!(self.username);
+                Some(record)
+            })
+            .collect()
+    }
+}
+
+impl Output for Game {
+    fn display_console(&self) {
+        println!(
+            "{} — {} — {}",
+            self.queue,
+            self.map,
+            display_game_time(self)
+        );
+        for (i, team) in self.teams.iter().enumerate() {
+            if i > 0 {
+                println!("\n");
+            }
+            // Unranked players and bots are left out rather than counted as the lowest rank
+            let ranks = team.players.iter().map(|p| &p.rank);
+            match RankOrdinal::average(ranks) {
+                Some((avg, count)) => println!(
+                    "Avg Team Rank: {} over {} of {} players",
+                    avg,
+                    count,
+                    team.players.len()
+                ),
+                None => println!("Avg Team Rank: N/A"),
+            }
+            let style = side_style(team.side);
+            gameHeader!(team.side.name(), style);
+            for person in &team.players {
+                display_participant_row(person, self.premades_checked);
+            }
+            if !team.bans.is_empty() {
+                let bans: Vec<&str> = team.bans.iter().map(|b| &b.champion[..]).collect();
+                println!("Bans: {}", bans.join(", "));
+            }
+        }
+    }
+
+    fn records(&self) -> Vec<Value> {
+        self.teams
+            .iter()
+            .flat_map(|team| team.players.iter())
+            .filter_map(|p| {
+                let mut record = serde_json::to_value(p).ok()?;
+                record["mode"] = json!(self.mode);
+                Some(record)
+            })
+            .collect()
+    }
+}
+
+impl Output for ChampionPool {
+    fn display_console(&self) {
+        let yellow: Style = Style::new().yellow();
+        let label = format!(" {} Champions ", &self.username);
+        println!("{:=^88}", yellow.apply_to(&label));
+        let games: u32 = self.champions.iter().map(|c| c.games).sum();
+        println!("{} champions over {} games", self.champions.len(), games);
+        println!(
+            "{0: <14} | {1: ^5} | {2: ^7} | {3: ^6} | {4: ^14} | {5: ^4} | {6: ^4} | {7}",
+            CHAMPION_COLS[0],
+            CHAMPION_COLS[1],
+            CHAMPION_COLS[2],
+            CHAMPION_COLS[3],
+            CHAMPION_COLS[4],
+            CHAMPION_COLS[5],
+            CHAMPION_COLS[6],
+            CHAMPION_COLS[7]
+        );
+        println!(
+            "{:-<15}+{:-<7}+{:-<9}+{:-<8}+{:-<16}+{:-<6}+{:-<6}+{:-<14}",
+            "-", "-", "-", "-", "-", "-", "-", "-"
+        );
+        for champion in &self.champions {
+            display_champion_row(champion);
+        }
+    }
+
+    fn records(&self) -> Vec<Value> {
+        self.champions
+            .iter()
+            .filter_map(|champion| {
+                let mut record = serde_json::to_value(champion).ok()?;
+                record["username"] = json!(self.username);
+                Some(record)
+            })
+            .collect()
+    }
+}
+
+fn display_champion_row(c: &ChampionStats) {
+    let mastery = match &c.mastery {
+        Some(m) => format_mastery(m),
+        None => String::from("-"),
+    };
+    println!(
+        "{0: <14} | {1: ^5} | {2: ^7} | {3: >5.1}% | {4: ^14} | {5: ^4.1} | {6: ^4.1} | {7}",
+        c.champion,
+        c.games,
+        format!("{}/{}", c.wins, c.losses),
+        c.win_rate(),
+        format!("{:.1}/{:.1}/{:.1}", c.kills, c.deaths, c.assists),
+        c.kda_ratio(),
+        c.cs_per_min,
+        mastery
+    );
+}
+
+impl Output for MatchDetails {
+    fn display_console(&self) {
+        let yellow: Style = Style::new().yellow();
+        let label = format!(" Match {} ", self.match_id);
+        println!("{:=^106}", yellow.apply_to(&label));
+        println!(
+            "{} | {} | {} | {}",
+            self.queue,
+            self.map,
+            format_duration(self.duration),
+            format_start(self.started)
+        );
+        for team in &self.teams {
+            let (name, style) = match team.team_id {
+                100 => ("Blue Team", Style::new().cyan()),
+                _ => ("Red Team", Style::new().red()),
+            };
+            let outcome = match (self.remake, team.win) {
+                (true, _) => "Remake",
+                (false, true) => "Victory",
+                (false, false) => "Defeat",
+            };
+            println!();
+            println!(
+                "{:=^106}",
+                style.apply_to(format!(" {} - {} ", name, outcome))
+            );
+            println!(
+                "{0: <17} | {1: ^14} | {2: ^3} | {3: ^8} | {4: ^4} | {5: ^6} | {6: ^6} | {7: ^6} | {8}",
+                SCOREBOARD_COLS[0],
+                SCOREBOARD_COLS[1],
+                SCOREBOARD_COLS[2],
+                SCOREBOARD_COLS[3],
+                SCOREBOARD_COLS[4],
+                SCOREBOARD_COLS[5],
+                SCOREBOARD_COLS[6],
+                SCOREBOARD_COLS[7],
+                SCOREBOARD_COLS[8]
+            );
+            println!(
+                "{:-<18}+{:-<16}+{:-<5}+{:-<10}+{:-<6}+{:-<8}+{:-<8}+{:-<8}+{:-<19}",
+                "-", "-", "-", "-", "-", "-", "-", "-", "-"
+            );
+            for player in &team.players {
+                display_scoreboard_row(player);
+            }
+        }
+    }
+
+    fn records(&self) -> Vec<Value> {
+        self.teams
+            .iter()
+            .flat_map(|team| team.players.iter().map(move |p| (team, p)))
+            .filter_map(|(team, p)| {
+                let mut record = serde_json::to_value(p).ok()?;
+                record["match_id"] = json!(self.match_id);
+                record["team_id"] = json!(team.team_id);
+                record["win"] = json!(team.win && !self.remake);
+                Some(record)
+            })
+            .collect()
+    }
+}
+
+fn display_scoreboard_row(p: &PlayerStats) {
+    println!(
+        "{0: <17} | {1: ^14} | {2: ^3} | {3: ^8} | {4: ^4} | {5: ^6} | {6: ^6} | {7: ^6} | {8}",
+        p.name,
+        p.champion,
+        p.level,
+        p.kda(),
+        p.cs,
+        p.gold,
+        p.damage,
+        p.vision_score,
+        display_runes(&p.keystone, &p.secondary_tree)
+    );
+    println!("{: <17} | Items: {}", "", p.items.join(", "));
+}
+
+fn display_rank_row(rank: &Rank) {
+    // The emoji is two columns wide so its column is one narrower
+    let streak_width = if utf8_supported() { 9 } else { 10 };
+    println!(
+        "{0: ^17} | {1: ^6} | {2: ^6} | {3: ^6} | {4: ^5$}",
+        rank.queue_type,
+        rank.short(),
+        style_wl(rank),
+        display_lp(rank),
+        display_streak(rank),
+        streak_width
+    );
+}
+
+fn display_participant_row(p: &Participant, premades_checked: bool) {
+    // The looked up player stands out in their team's table
+    let name = match p.searched {
+        true => Style::new().bold().yellow(),
+        false => Style::new(),
+    };
+    println!(
+        "{0: <17} | {1: ^5} | {2: ^6} | {3: ^6} | {4: ^6} | {5: ^14} | {6: <18} | {7: <28} | {8}",
+        name.apply_to(&p.name),
+        display_premade(p.premade, premades_checked),
+        p.rank.short(),
+        display_lp(&p.rank),
+        style_wl(&p.rank),
+        p.champion,
+        display_spells(&p.spells),
+        display_runes(&p.keystone, &p.secondary_tree),
+        display_streak(&p.rank),
+    );
+    if let Some(stats) = &p.champion_stats {
+        println!("{: <17} | {}", "", display_live_champion(stats));
+    }
+}
+
+// e.g. "Aatrox: 12 games, 58% win rate, Lvl 7 (254,321 pts)"
+fn display_live_champion(stats: &ChampionStats) -> String {
+    let games = match stats.games {
+        0 => String::from("no recent games"),
+        1 => format!("1 game, {:.0}% win rate", stats.win_rate()),
+        n => format!("{} games, {:.0}% win rate", n, stats.win_rate()),
+    };
+    let mastery = match &stats.mastery {
+        Some(m) => format_mastery(m),
+        None => String::from("no mastery"),
+    };
+    format!("{}: {}, {}", stats.champion, games, mastery)
+}
+
+fn format_mastery(m: &Mastery) -> String {
+    format!("Lvl {} ({} pts)", m.level, format_points(m.points))
+}
+
+// Groups are lettered so they do not read as a rank or count, e.g. "A" for the first duo.
+// Without --premades nobody was checked, which is shown as "-" rather than as playing alone.
+fn display_premade(group: Option<u8>, checked: bool) -> String {
+    match group {
+        Some(n) => ((b'A' + (n - 1) % 26) as char).to_string(),
+        None if !checked => String::from("-"),
+        None => String::new(),
+    }
+}
+
+fn display_spells(spells: &[String]) -> String {
+    match spells.is_empty() {
+        true => String::from("-"),
+        false => spells.join(", "),
+    }
+}
+
+// The keystone and secondary tree, e.g. "Conqueror / Resolve". Bots have no runes.
+fn display_runes(keystone: &str, secondary_tree: &str) -> String {
+    match keystone.is_empty() {
+        true => String::from("-"),
+        false => format!("{} / {}", keystone, secondary_tree),
+    }
+}
+
+fn side_style(side: Side) -> Style {
+    match side {
+        Side::Blue => Style::new().cyan(),
+        Side::Red => Style::new().red(),
+        Side::Subteam(_) => Style::new().yellow(),
+    }
+}
+
+fn style_wl(rank: &Rank) -> StyledObject<String> {
+    let red = Style::new().red();
+    let green = Style::new().green();
+    let default = Style::new();
+    match rank.win_rate() {
+        None => default.apply_to("N/A".to_string()),
+        Some(rate) if rate > 55.0 => green.apply_to(format!("{0:.2}%", rate)),
+        Some(rate) if rate < 48.0 => red.apply_to(format!("{0:.2}%", rate)),
+        Some(rate) => default.apply_to(format!("{0:.2}%", rate)),
+    }
+}
+
+fn display_lp(rank: &Rank) -> String {
+    match rank.is_ranked() {
+        true => rank.league_points.to_string(),
+        false => String::from("-"),
+    }
+}
+
+fn display_streak(rank: &Rank) -> &str {
+    if rank.hot_streak {
+        emoji!(FIRE, "Y")
+    } else {
+        emoji!(COLD, "N")
+    }
+}
+
+fn utf8_supported() -> bool {
+    match std::env::var("LANG") {
+        Ok(lang) => lang.to_uppercase().ends_with("UTF-8"),
+        _ => false,
+    }
+}
+
+// The time played, counted from the start time so a cached lookup stays current
+fn display_game_time(game: &Game) -> String {
+    if game.started <= 0 {
+        return String::from("loading");
+    }
+    let elapsed = (Utc::now().timestamp_millis() - game.started) / 1000;
+    let elapsed = match elapsed > 0 {
+        true => elapsed as u32,
+        false => game.length,
+    };
+    format!("{} in", format_duration(elapsed))
+}
+
+// Formats a game length in seconds as m:ss
+fn format_duration(seconds: u32) -> String {
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+// Shortens a value to `width` characters, ending it with "." when it was cut
+fn fit(value: &str, width: usize) -> String {
+    match value.chars().count() > width {
+        true => format!("{}.", value.chars().take(width - 1).collect::<String>()),
+        false => value.to_string(),
+    }
+}
+
+fn format_start(millis: i64) -> String {
+    match DateTime::from_timestamp_millis(millis) {
+        Some(start) if millis > 0 => start.format("%Y-%m-%d %H:%M UTC").to_string(),
+        _ => String::from("Unknown date"),
+    }
+}
+
+// Formats mastery points with thousands separators, e.g. 1,234,567
+fn format_points(points: u32) -> String {
+    let digits = points.to_string();
+    let mut out = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            out.push(',');
+        }
+        out.push(c);
+    }
+    out
+}
+
+// How long ago a time in milliseconds since the epoch was, e.g. "3 days ago"
+fn format_ago(millis: i64) -> String {
+    if millis <= 0 {
+        return String::from("Never");
+    }
+    let days = (Utc::now().timestamp_millis() - millis) / (24 * 60 * 60 * 1000);
+    match days {
+        d if d < 1 => String::from("Today"),
+        1 => String::from("Yesterday"),
+        d if d < 60 => format!("{} days ago", d),
+        d if d < 730 => format!("{} months ago", d / 30),
+        d => format!("{} years ago", d / 365),
+    }
+}
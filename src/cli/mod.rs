@@ -0,0 +1,5 @@
+/// This file contains the command line front end built on the lolookup library
+pub mod app;
+pub mod config;
+pub mod display;
+pub mod output;
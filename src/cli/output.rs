@@ -0,0 +1,64 @@
+/// This file contains the output formats a command's result can be printed in
+use lolookup::ProgramError;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    #[default]
+    Table,
+    Json,
+    Ndjson,
+}
+
+impl FromStr for OutputFormat {
+    type Err = ProgramError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match &s.to_lowercase()[..] {
+            "table" => Ok(OutputFormat::Table),
+            "json" => Ok(OutputFormat::Json),
+            "ndjson" => Ok(OutputFormat::Ndjson),
+            _ => Err(ProgramError::InvalidArgument(format!(
+                "Unknown format '{}'. Valid formats: table, json, ndjson",
+                s
+            ))),
+        }
+    }
+}
+
+// A command result that can be printed as a table or serialised for scripts
+pub trait Output: Serialize {
+    fn display_console(&self);
+
+    // The records written one per line in ndjson mode
+    fn records(&self) -> Vec<Value> {
+        serde_json::to_value(self).into_iter().collect()
+    }
+}
+
+pub fn print<T: Output>(value: &T, format: OutputFormat) {
+    match format {
+        OutputFormat::Table => value.display_console(),
+        OutputFormat::Json => match serde_json::to_string_pretty(value) {
+            Ok(data) => println!("{}", data),
+            Err(_) => print_error(&ProgramError::DeserializeError, format),
+        },
+        OutputFormat::Ndjson => {
+            for record in value.records() {
+                println!("{}", record);
+            }
+        }
+    }
+}
+
+// Errors are printed as a JSON object in the machine readable formats so scripts can detect them
+pub fn print_error(e: &ProgramError, format: OutputFormat) {
+    match format {
+        OutputFormat::Table => println!("{}", e),
+        OutputFormat::Json | OutputFormat::Ndjson => {
+            println!("{}", json!({ "error": e.to_string() }))
+        }
+    }
+}
@@ -0,0 +1,491 @@
+/// This file contains the client every Riot API request goes through
+use crate::cache::Cache;
+use crate::error::ProgramError;
+use crate::region::Region;
+use rand::Rng;
+use reqwest::header::{HeaderMap, HeaderValue};
+use serde::de::DeserializeOwned;
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+use tokio::time::delay_for;
+
+const X_RIOT_TOKEN: &str = "X-Riot-Token";
+const APP_LIMIT_HEADER: &str = "X-App-Rate-Limit";
+const APP_COUNT_HEADER: &str = "X-App-Rate-Limit-Count";
+const METHOD_LIMIT_HEADER: &str = "X-Method-Rate-Limit";
+const METHOD_COUNT_HEADER: &str = "X-Method-Rate-Limit-Count";
+const RETRY_AFTER_HEADER: &str = "Retry-After";
+// The limits of a development key, used until Riot tells us the real ones
+const DEFAULT_APP_LIMIT: &str = "20:1,100:120";
+const DEFAULT_RETRY_AFTER: u64 = 1;
+const MAX_RATE_LIMIT_RETRIES: u8 = 3;
+const DEFAULT_RETRIES: u32 = 3;
+const DEFAULT_TIMEOUT: u64 = 10;
+const BASE_BACKOFF_MS: u64 = 500;
+const MAX_BACKOFF_MS: u64 = 8000;
+
+// The Riot endpoints the tool calls. Each one has its own rate limit and cache lifetime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Endpoint {
+    RiotAccount,
+    Summoner,
+    LeagueEntries,
+    ActiveGame,
+    MatchIds,
+    Match,
+    ChampionMastery,
+}
+
+impl Endpoint {
+    // The method name Riot uses for the endpoint's rate limit
+    pub fn method(self) -> &'static str {
+        match self {
+            Endpoint::RiotAccount => "account-v1.getByRiotId",
+            Endpoint::Summoner => "summoner-v4.getByPUUID",
+            Endpoint::LeagueEntries => "league-v4.getLeagueEntriesByPUUID",
+            Endpoint::ActiveGame => "spectator-v5.getCurrentGameInfoByPuuid",
+            Endpoint::MatchIds => "match-v5.getMatchIdsByPUUID",
+            Endpoint::Match => "match-v5.getMatch",
+            Endpoint::ChampionMastery => "champion-mastery-v4.getAllChampionMasteriesByPUUID",
+        }
+    }
+
+    // How long a response stays fresh. None means it never changes.
+    pub fn ttl(self) -> Option<Duration> {
+        match self {
+            Endpoint::RiotAccount => Some(Duration::from_secs(24 * 60 * 60)),
+            Endpoint::Summoner => Some(Duration::from_secs(10 * 60)),
+            Endpoint::LeagueEntries => Some(Duration::from_secs(5 * 60)),
+            Endpoint::ActiveGame => Some(Duration::from_secs(30)),
+            Endpoint::MatchIds => Some(Duration::from_secs(2 * 60)),
+            Endpoint::Match => None,
+            Endpoint::ChampionMastery => Some(Duration::from_secs(30 * 60)),
+        }
+    }
+}
+
+// The status and body of a response, whether it came from Riot or the cache
+pub struct ApiResponse {
+    pub status: u16,
+    pub body: String,
+}
+
+impl ApiResponse {
+    pub fn json<T: DeserializeOwned>(&self) -> Result<T, ProgramError> {
+        serde_json::from_str(&self.body).map_err(|_| ProgramError::DeserializeError)
+    }
+}
+
+// How failed requests are retried. Server errors and network failures are retried with an
+// exponential backoff, everything else is handed back to the caller straight away.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    pub retries: u32,
+    pub timeout: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            retries: DEFAULT_RETRIES,
+            timeout: Duration::from_secs(DEFAULT_TIMEOUT),
+        }
+    }
+}
+
+impl RetryPolicy {
+    // Doubles the delay after every attempt and picks a random point in its upper half so
+    // concurrent requests do not retry in lock step
+    fn backoff(&self, attempt: u32) -> Duration {
+        let exp = BASE_BACKOFF_MS.saturating_mul(1 << attempt.min(16).saturating_sub(1));
+        let delay = exp.min(MAX_BACKOFF_MS);
+        let jittered = rand::thread_rng().gen_range(delay / 2, delay + 1);
+        Duration::from_millis(jittered)
+    }
+}
+
+pub(crate) struct RiotClient {
+    http: reqwest::Client,
+    api_key: String,
+    region: Region,
+    retry: RetryPolicy,
+    cache: Cache,
+    limiter: Mutex<Limiter>,
+    // Held by the request waiting for its turn to be sent
+    queue: Mutex<()>,
+}
+
+impl RiotClient {
+    pub fn new(api_key: &str, region: Region, retry: RetryPolicy, cache: Cache) -> Self {
+        let http = reqwest::Client::builder()
+            .timeout(retry.timeout)
+            .build()
+            .unwrap_or_else(|_| reqwest::Client::new());
+        RiotClient {
+            http,
+            api_key: api_key.to_string(),
+            region,
+            retry,
+            cache,
+            limiter: Mutex::new(Limiter::default()),
+            queue: Mutex::new(()),
+        }
+    }
+
+    pub fn region(&self) -> Region {
+        self.region
+    }
+
+    // Answers from the cache when it holds a fresh copy of `url`, otherwise fetches it from Riot
+    pub async fn get(&self, url: &str, endpoint: Endpoint) -> Result<ApiResponse, ProgramError> {
+        if let Some(body) = self.cache.get(url, endpoint.ttl()) {
+            return Ok(ApiResponse { status: 200, body });
+        }
+        let res = self.send(url, endpoint.method()).await?;
+        if res.status == 200 {
+            self.cache.put(url, &res.body);
+        }
+        Ok(res)
+    }
+
+    // Sends a GET request once the app and method rate limits allow it. The method has its own
+    // limit on top of the app limit.
+    async fn send(&self, url: &str, method: &str) -> Result<ApiResponse, ProgramError> {
+        let token = HeaderValue::from_str(&self.api_key).map_err(|_| ProgramError::Unauthorized)?;
+        let host = reqwest::Url::parse(url)
+            .ok()
+            .and_then(|u| u.host_str().map(String::from))
+            .ok_or(ProgramError::InvalidUrl)?;
+
+        let mut attempts = 0;
+        let mut rate_limited = 0;
+        loop {
+            self.acquire(&host, method).await;
+            attempts += 1;
+            let sent = self
+                .http
+                .get(url)
+                .header(X_RIOT_TOKEN, token.clone())
+                .send()
+                .await;
+            let res = match sent {
+                Ok(res) => res,
+                Err(e) if is_transient(&e) && attempts <= self.retry.retries => {
+                    delay_for(self.retry.backoff(attempts)).await;
+                    continue;
+                }
+                Err(e) => return Err(ProgramError::RequestFailed(attempts, describe(&e))),
+            };
+            self.limiter
+                .lock()
+                .await
+                .update(&host, method, res.headers());
+
+            match res.status().as_u16() {
+                401 | 403 => return Err(ProgramError::Unauthorized),
+                429 if rate_limited < MAX_RATE_LIMIT_RETRIES => {
+                    // Waiting out a rate limit does not count as a failed attempt
+                    rate_limited += 1;
+                    attempts -= 1;
+                    let wait = retry_after(res.headers());
+                    self.limiter.lock().await.block(&host, wait);
+                }
+                429 => return Err(ProgramError::RateLimited),
+                500 | 502 | 503 | 504 if attempts <= self.retry.retries => {
+                    delay_for(self.retry.backoff(attempts)).await;
+                }
+                status @ 500..=504 => {
+                    let reason = format!("server responded with {}", status);
+                    return Err(ProgramError::RequestFailed(attempts, reason));
+                }
+                status => {
+                    let body = res
+                        .text()
+                        .await
+                        .map_err(|_| ProgramError::DeserializeError)?;
+                    return Ok(ApiResponse { status, body });
+                }
+            }
+        }
+    }
+
+    // Waits until a request to `host` can be sent without going over a limit. Requests take turns
+    // so they are let through in the order they were queued. The limiter itself is only locked
+    // to check the counts, so responses can update it while a request sleeps.
+    async fn acquire(&self, host: &str, method: &str) {
+        let _turn = self.queue.lock().await;
+        loop {
+            let now = Instant::now();
+            let wait = {
+                let mut limiter = self.limiter.lock().await;
+                match limiter.wait_time(host, method, now) {
+                    Some(wait) => wait,
+                    None => {
+                        limiter.record(host, method, now);
+                        return;
+                    }
+                }
+            };
+            delay_for(wait).await;
+        }
+    }
+}
+
+// Timeouts and dropped connections are worth another try, a malformed request is not
+fn is_transient(e: &reqwest::Error) -> bool {
+    e.is_timeout() || e.is_request() || e.is_connect()
+}
+
+fn describe(e: &reqwest::Error) -> String {
+    if e.is_timeout() {
+        String::from("request timed out")
+    } else if e.is_connect() {
+        String::from("unable to connect to the Riot API")
+    } else {
+        e.to_string()
+    }
+}
+
+// Reads the Retry-After header of a 429 response
+fn retry_after(headers: &HeaderMap) -> Duration {
+    let secs = headers
+        .get(RETRY_AFTER_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse().ok())
+        .unwrap_or(DEFAULT_RETRY_AFTER);
+    Duration::from_secs(secs)
+}
+
+// Request bookkeeping for every routing host (na1, americas, ...) the client has talked to
+#[derive(Default)]
+struct Limiter {
+    app: HashMap<String, RateLimit>,
+    methods: HashMap<(String, String), RateLimit>,
+    blocked_until: HashMap<String, Instant>,
+}
+
+impl Limiter {
+    fn wait_time(&mut self, host: &str, method: &str, now: Instant) -> Option<Duration> {
+        let blocked = self
+            .blocked_until
+            .get(host)
+            .and_then(|until| until.checked_duration_since(now));
+        let app = self
+            .app
+            .entry(host.to_string())
+            .or_insert_with(|| RateLimit::parse(DEFAULT_APP_LIMIT))
+            .wait_time(now);
+        let method = self
+            .methods
+            .get_mut(&(host.to_string(), method.to_string()))
+            .and_then(|limit| limit.wait_time(now));
+        vec![blocked, app, method].into_iter().flatten().max()
+    }
+
+    fn record(&mut self, host: &str, method: &str, now: Instant) {
+        if let Some(limit) = self.app.get_mut(host) {
+            limit.record(now);
+        }
+        if let Some(limit) = self
+            .methods
+            .get_mut(&(host.to_string(), method.to_string()))
+        {
+            limit.record(now);
+        }
+    }
+
+    // Picks up the limits and counts Riot reports for the key and the endpoint
+    fn update(&mut self, host: &str, method: &str, headers: &HeaderMap) {
+        let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+        if let Some(limits) = header(APP_LIMIT_HEADER) {
+            let limit = self
+                .app
+                .entry(host.to_string())
+                .or_insert_with(|| RateLimit::parse(limits));
+            limit.set_limits(limits);
+            if let Some(counts) = header(APP_COUNT_HEADER) {
+                limit.sync_counts(counts);
+            }
+        }
+        if let Some(limits) = header(METHOD_LIMIT_HEADER) {
+            let limit = self
+                .methods
+                .entry((host.to_string(), method.to_string()))
+                .or_insert_with(|| RateLimit::parse(limits));
+            limit.set_limits(limits);
+            if let Some(counts) = header(METHOD_COUNT_HEADER) {
+                limit.sync_counts(counts);
+            }
+        }
+    }
+
+    // Holds back every request to `host` after a 429
+    fn block(&mut self, host: &str, wait: Duration) {
+        self.blocked_until
+            .insert(host.to_string(), Instant::now() + wait);
+    }
+}
+
+// All the windows of one limit, e.g. "20:1,100:120" is 20 requests per second and 100 per two minutes
+struct RateLimit {
+    buckets: Vec<Bucket>,
+}
+
+struct Bucket {
+    limit: usize,
+    window: Duration,
+    sent: VecDeque<Instant>,
+}
+
+impl RateLimit {
+    fn parse(header: &str) -> Self {
+        let buckets = parse_pairs(header)
+            .into_iter()
+            .map(|(limit, window)| Bucket {
+                limit: limit as usize,
+                window: Duration::from_secs(window),
+                sent: VecDeque::new(),
+            })
+            .collect();
+        RateLimit { buckets }
+    }
+
+    // Replaces the limits while keeping track of the requests already sent
+    fn set_limits(&mut self, header: &str) {
+        let mut new = RateLimit::parse(header);
+        for bucket in new.buckets.iter_mut() {
+            if let Some(old) = self.buckets.iter_mut().find(|b| b.window == bucket.window) {
+                bucket.sent = std::mem::take(&mut old.sent);
+            }
+        }
+        *self = new;
+    }
+
+    // Other processes using the same key count towards the limit as well
+    fn sync_counts(&mut self, header: &str) {
+        let now = Instant::now();
+        for (count, window) in parse_pairs(header) {
+            let window = Duration::from_secs(window);
+            if let Some(bucket) = self.buckets.iter_mut().find(|b| b.window == window) {
+                while bucket.sent.len() < count as usize {
+                    bucket.sent.push_back(now);
+                }
+            }
+        }
+    }
+
+    fn wait_time(&mut self, now: Instant) -> Option<Duration> {
+        self.buckets
+            .iter_mut()
+            .filter_map(|bucket| {
+                while let Some(sent) = bucket.sent.front() {
+                    if now.duration_since(*sent) >= bucket.window {
+                        bucket.sent.pop_front();
+                    } else {
+                        break;
+                    }
+                }
+                // A limit of 0 would never let a request through, so it is not enforced
+                if bucket.limit == 0 || bucket.sent.len() < bucket.limit {
+                    return None;
+                }
+                // Wait for the oldest request that has to leave the window to make room
+                let oldest = bucket.sent[bucket.sent.len() - bucket.limit];
+                Some((oldest + bucket.window).saturating_duration_since(now))
+            })
+            .max()
+    }
+
+    fn record(&mut self, now: Instant) {
+        for bucket in self.buckets.iter_mut() {
+            bucket.sent.push_back(now);
+        }
+    }
+}
+
+// Parses "20:1,100:120" into [(20, 1), (100, 120)]
+fn parse_pairs(header: &str) -> Vec<(u64, u64)> {
+    header
+        .split(',')
+        .filter_map(|pair| {
+            let mut parts = pair.trim().split(':');
+            let first = parts.next()?.parse().ok()?;
+            let second = parts.next()?.parse().ok()?;
+            Some((first, second))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_limit_headers() {
+        assert_eq!(parse_pairs("20:1,100:120"), vec![(20, 1), (100, 120)]);
+        assert_eq!(parse_pairs(" 20:1, x:2,3"), vec![(20, 1)]);
+    }
+
+    #[test]
+    fn full_bucket_waits_for_the_oldest_request() {
+        let mut limit = RateLimit::parse("20:1,100:120");
+        let start = Instant::now();
+        for _ in 0..19 {
+            limit.record(start);
+        }
+        assert_eq!(limit.wait_time(start), None);
+        limit.record(start);
+        assert_eq!(limit.wait_time(start), Some(Duration::from_secs(1)));
+        assert_eq!(
+            limit.wait_time(start + Duration::from_millis(400)),
+            Some(Duration::from_millis(600))
+        );
+        // The requests leave the one second window together
+        assert_eq!(limit.wait_time(start + Duration::from_secs(1)), None);
+    }
+
+    #[test]
+    fn longest_window_decides_the_wait() {
+        let mut limit = RateLimit::parse("20:1,100:120");
+        let start = Instant::now();
+        for i in 0..100 {
+            limit.record(start + Duration::from_secs(i / 20));
+        }
+        let now = start + Duration::from_secs(10);
+        assert_eq!(limit.wait_time(now), Some(Duration::from_secs(110)));
+    }
+
+    #[test]
+    fn synced_counts_fill_the_bucket() {
+        let mut limit = RateLimit::parse("20:1,100:120");
+        limit.sync_counts("20:1,45:120");
+        let wait = limit.wait_time(Instant::now()).unwrap();
+        assert!(wait <= Duration::from_secs(1));
+
+        // A lower count than the requests already recorded leaves them in place
+        limit.sync_counts("1:1,1:120");
+        assert!(limit.wait_time(Instant::now()).is_some());
+    }
+
+    #[test]
+    fn zero_limit_is_ignored() {
+        let mut limit = RateLimit::parse("0:1,2:10");
+        let start = Instant::now();
+        limit.record(start);
+        assert_eq!(limit.wait_time(start), None);
+        limit.record(start);
+        assert_eq!(limit.wait_time(start), Some(Duration::from_secs(10)));
+    }
+
+    #[test]
+    fn new_limits_keep_the_sent_requests() {
+        let mut limit = RateLimit::parse("20:1,100:120");
+        let start = Instant::now();
+        for _ in 0..10 {
+            limit.record(start);
+        }
+        limit.set_limits("10:1,100:120");
+        assert_eq!(limit.wait_time(start), Some(Duration::from_secs(1)));
+    }
+}
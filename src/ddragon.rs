@@ -0,0 +1,93 @@
+/// This file contains the downloads of Riot's static game data from Data Dragon
+use crate::cache::cache_dir;
+use crate::error::ProgramError;
+use serde::de::DeserializeOwned;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+const DDRAGON_HOST: &str = "https://ddragon.leagueoflegends.com";
+// Queue, map and game mode ids are documented outside Data Dragon and are not versioned
+const DOCS_HOST: &str = "https://static.developer.riotgames.com/docs/lol";
+const STATIC_DIR: &str = "static";
+const LOCALE: &str = "en_US";
+const DOWNLOAD_TIMEOUT: u64 = 30;
+
+// Reads a static data file saved by a previous download
+pub fn read_file<T: DeserializeOwned>(name: &str) -> Option<T> {
+    let data = fs::read_to_string(static_path(name)?).ok()?;
+    serde_json::from_str(&data).ok()
+}
+
+// Downloads a versioned data file, e.g. "champion.json", and saves it for the next runs
+pub async fn download_file(name: &str, version: &str) -> Result<String, ProgramError> {
+    let url = format!("{}/cdn/{}/data/{}/{}", DDRAGON_HOST, version, LOCALE, name);
+    let body = download(&url).await?;
+    save(name, &body);
+    Ok(body)
+}
+
+// Downloads and parses one of the id tables from Riot's developer docs, e.g. "queues.json"
+pub async fn fetch_docs<T: DeserializeOwned>(name: &str) -> Result<T, ProgramError> {
+    let body = download(&format!("{}/{}", DOCS_HOST, name)).await?;
+    save(name, &body);
+    serde_json::from_str(&body).map_err(|_| ProgramError::DeserializeError)
+}
+
+// Downloads and parses a data file of `version`, or of the latest patch when no version is given
+pub async fn fetch<T: DeserializeOwned>(
+    name: &str,
+    version: Option<&str>,
+) -> Result<T, ProgramError> {
+    let version = match version {
+        Some(v) => v.to_string(),
+        None => latest_version().await?,
+    };
+    let body = download_file(name, &version).await?;
+    serde_json::from_str(&body).map_err(|_| ProgramError::DeserializeError)
+}
+
+// Returns the newest patch version, e.g. "14.20.1"
+pub async fn latest_version() -> Result<String, ProgramError> {
+    let url = format!("{}/api/versions.json", DDRAGON_HOST);
+    let body = download(&url).await?;
+    let versions: Vec<String> =
+        serde_json::from_str(&body).map_err(|_| ProgramError::DeserializeError)?;
+    versions
+        .into_iter()
+        .next()
+        .ok_or(ProgramError::StaticDataUnavailable)
+}
+
+async fn download(url: &str) -> Result<String, ProgramError> {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(DOWNLOAD_TIMEOUT))
+        .build()
+        .map_err(|_| ProgramError::StaticDataUnavailable)?;
+    let res = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|_| ProgramError::StaticDataUnavailable)?;
+    match res.status().as_u16() {
+        200 => res
+            .text()
+            .await
+            .map_err(|_| ProgramError::StaticDataUnavailable),
+        404 => Err(ProgramError::InvalidVersion),
+        _ => Err(ProgramError::StaticDataUnavailable),
+    }
+}
+
+fn save(name: &str, body: &str) {
+    if let Some(path) = static_path(name) {
+        if let Some(dir) = path.parent() {
+            let _ = fs::create_dir_all(dir);
+        }
+        let _ = fs::write(path, body);
+    }
+}
+
+fn static_path(name: &str) -> Option<PathBuf> {
+    cache_dir().map(|dir| dir.join(STATIC_DIR).join(name))
+}
@@ -0,0 +1,72 @@
+/// This file contains the errors returned by every lookup
+use crate::region::REGIONS;
+use crate::API_KEY_ENV;
+use std::error::Error;
+use std::fmt;
+use std::result;
+
+pub type Result<T> = result::Result<T, ProgramError>;
+
+#[derive(Debug)]
+pub enum ProgramError {
+    DeserializeError,
+    InvalidUrl,
+    NotInGame,
+    InvalidResponse,
+    InvalidAccount,
+    BadResponse,
+    NoHistory,
+    InvalidMatch,
+    InvalidRegion(String),
+    ConfigError,
+    MissingApiKey,
+    Unauthorized,
+    RateLimited,
+    RequestFailed(u32, String),
+    StaticDataUnavailable,
+    InvalidVersion,
+    InvalidArgument(String),
+}
+
+impl fmt::Display for ProgramError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ProgramError::NotInGame => write!(f, "Summoner is not in game."),
+            ProgramError::InvalidAccount => write!(f, "Account does not exist."),
+            ProgramError::InvalidUrl => write!(f, "Invalid url"),
+            ProgramError::BadResponse => write!(f, "Bad response"),
+            ProgramError::DeserializeError => write!(f, "Error deserializing JSON"),
+            ProgramError::InvalidResponse => {
+                write!(f, "Invalid response, Status code not 404 or 200")
+            }
+            ProgramError::NoHistory => write!(f, "No history available"),
+            ProgramError::InvalidMatch => write!(f, "Match does not exist."),
+            ProgramError::InvalidRegion(r) => {
+                let valid: Vec<&str> = REGIONS.iter().map(|r| r.id()).collect();
+                write!(f, "Unknown region '{}'. Valid regions: {}", r, valid.join(", "))
+            }
+            ProgramError::ConfigError => write!(f, "Unable to read or write the config file"),
+            ProgramError::RateLimited => {
+                write!(f, "Rate limit exceeded. Wait a few minutes and try again.")
+            }
+            ProgramError::RequestFailed(attempts, reason) => {
+                let plural = if *attempts == 1 { "" } else { "s" };
+                write!(f, "Request failed after {} attempt{}: {}", attempts, plural, reason)
+            }
+            ProgramError::InvalidArgument(msg) => write!(f, "{}", msg),
+            ProgramError::StaticDataUnavailable => write!(f, "Unable to download data from Data Dragon"),
+            ProgramError::InvalidVersion => write!(f, "Data Dragon has no data for that patch"),
+            ProgramError::MissingApiKey => write!(
+                f,
+                "No Riot API key set. Use --api-key, set {}, or run 'lol key <api-key>'.",
+                API_KEY_ENV
+            ),
+            ProgramError::Unauthorized => write!(
+                f,
+                "Riot rejected the API key. Development keys expire after 24 hours, generate a new one at https://developer.riotgames.com"
+            ),
+        }
+    }
+}
+
+impl Error for ProgramError {}
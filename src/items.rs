@@ -0,0 +1,59 @@
+/// This file contains the item names from Data Dragon
+use crate::ddragon;
+use crate::error::ProgramError;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+const ITEMS_FILE: &str = "item.json";
+
+#[derive(Deserialize, Debug)]
+struct ItemFileJSON {
+    data: HashMap<String, ItemJSON>,
+}
+
+#[derive(Deserialize, Debug)]
+struct ItemJSON {
+    name: String,
+}
+
+// Item names by id
+pub struct Items {
+    by_id: HashMap<u32, String>,
+}
+
+impl Items {
+    // Uses the saved Data Dragon file, downloading the one of `version`, or of the latest patch,
+    // on the first run. Unknown items are shown by id when Data Dragon cannot be reached.
+    pub async fn load(version: Option<&str>) -> Self {
+        if let Some(file) = ddragon::read_file::<ItemFileJSON>(ITEMS_FILE) {
+            return Self::from_file(file);
+        }
+        match Self::refresh(version).await {
+            Ok(items) => items,
+            Err(_) => Items {
+                by_id: HashMap::new(),
+            },
+        }
+    }
+
+    pub async fn refresh(version: Option<&str>) -> Result<Self, ProgramError> {
+        let file: ItemFileJSON = ddragon::fetch(ITEMS_FILE, version).await?;
+        Ok(Self::from_file(file))
+    }
+
+    fn from_file(file: ItemFileJSON) -> Self {
+        let by_id = file
+            .data
+            .into_iter()
+            .filter_map(|(id, item)| Some((id.parse().ok()?, item.name)))
+            .collect();
+        Items { by_id }
+    }
+
+    pub fn name(&self, id: u32) -> String {
+        match self.by_id.get(&id) {
+            Some(name) => name.clone(),
+            None => format!("Item {}", id),
+        }
+    }
+}
@@ -0,0 +1,262 @@
+/// This file contains the raw Riot API responses before they are turned into models
+use serde::Deserialize;
+
+#[allow(non_snake_case)]
+#[derive(Deserialize, Debug)]
+// The Riot ID of a player, shared by every Riot game
+pub(crate) struct RiotAccountJSON {
+    pub puuid: String,
+    pub gameName: String,
+    pub tagLine: String,
+}
+
+#[derive(Deserialize, Debug)]
+// Represents a match fetch given the match id
+pub(crate) struct MatchDataJSON {
+    pub metadata: MatchMetadataJSON,
+    pub info: MatchInfoJSON,
+}
+
+impl MatchDataJSON {
+    pub fn participant(&self, puuid: &str) -> Option<&MatchParticipantJSON> {
+        self.info.participants.iter().find(|p| p.puuid == puuid)
+    }
+
+    pub fn team_kills(&self, team_id: u16) -> u32 {
+        self.team(team_id).map(|p| p.kills as u32).sum()
+    }
+
+    // Damage dealt to champions by the whole team
+    pub fn team_damage(&self, team_id: u16) -> u32 {
+        self.team(team_id)
+            .map(|p| p.totalDamageDealtToChampions)
+            .sum()
+    }
+
+    fn team(&self, team_id: u16) -> impl Iterator<Item = &MatchParticipantJSON> {
+        self.info
+            .participants
+            .iter()
+            .filter(move |p| p.teamId == team_id)
+    }
+}
+
+#[allow(non_snake_case)]
+#[derive(Deserialize, Debug)]
+pub(crate) struct MatchMetadataJSON {
+    pub matchId: String,
+}
+
+#[allow(non_snake_case)]
+#[derive(Deserialize, Debug)]
+pub(crate) struct MatchInfoJSON {
+    pub queueId: u16,
+    #[serde(default)]
+    pub mapId: u16,
+    #[serde(default)]
+    pub gameMode: String,
+    // Milliseconds since the epoch
+    #[serde(default)]
+    pub gameCreation: i64,
+    // Seconds
+    #[serde(default)]
+    pub gameDuration: u32,
+    pub participants: Vec<MatchParticipantJSON>,
+}
+
+#[allow(non_snake_case)]
+#[derive(Deserialize, Debug)]
+pub(crate) struct MatchParticipantJSON {
+    pub puuid: String,
+    #[serde(default)]
+    pub riotIdGameName: String,
+    #[serde(default)]
+    pub riotIdTagline: String,
+    pub teamId: u16,
+    pub championId: u16,
+    #[serde(default)]
+    pub champLevel: u8,
+    #[serde(default)]
+    pub teamPosition: String,
+    pub win: bool,
+    #[serde(default)]
+    pub gameEndedInEarlySurrender: bool,
+    #[serde(default)]
+    pub kills: u16,
+    #[serde(default)]
+    pub deaths: u16,
+    #[serde(default)]
+    pub assists: u16,
+    #[serde(default)]
+    pub totalMinionsKilled: u16,
+    #[serde(default)]
+    pub neutralMinionsKilled: u16,
+    #[serde(default)]
+    pub goldEarned: u32,
+    #[serde(default)]
+    pub totalDamageDealtToChampions: u32,
+    #[serde(default)]
+    pub visionScore: u16,
+    #[serde(default)]
+    pub item0: u32,
+    #[serde(default)]
+    pub item1: u32,
+    #[serde(default)]
+    pub item2: u32,
+    #[serde(default)]
+    pub item3: u32,
+    #[serde(default)]
+    pub item4: u32,
+    #[serde(default)]
+    pub item5: u32,
+    #[serde(default)]
+    pub item6: u32,
+    #[serde(default)]
+    pub perks: PerksJSON,
+}
+
+impl MatchParticipantJSON {
+    // The Riot ID at the time of the match
+    pub fn riot_id(&self) -> String {
+        format!("{}#{}", self.riotIdGameName, self.riotIdTagline)
+    }
+
+    // Lane minions plus jungle monsters
+    pub fn cs(&self) -> u16 {
+        self.totalMinionsKilled + self.neutralMinionsKilled
+    }
+
+    // The item ids in inventory order. Empty slots are skipped.
+    pub fn items(&self) -> Vec<u32> {
+        vec![
+            self.item0, self.item1, self.item2, self.item3, self.item4, self.item5, self.item6,
+        ]
+        .into_iter()
+        .filter(|&id| id != 0)
+        .collect()
+    }
+
+    // The keystone is the first rune of the primary tree
+    pub fn keystone(&self) -> Option<u16> {
+        let primary = self.perks.styles.first()?;
+        primary.selections.first().map(|s| s.perk)
+    }
+
+    pub fn secondary_tree(&self) -> Option<u16> {
+        self.perks.styles.get(1).map(|s| s.style)
+    }
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub(crate) struct PerksJSON {
+    #[serde(default)]
+    pub styles: Vec<PerkStyleJSON>,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct PerkStyleJSON {
+    pub style: u16,
+    #[serde(default)]
+    pub selections: Vec<PerkSelectionJSON>,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct PerkSelectionJSON {
+    pub perk: u16,
+}
+
+#[allow(non_snake_case)]
+#[derive(Deserialize, Debug)]
+pub(crate) struct ChampionMasteryJSON {
+    pub championId: u16,
+    pub championLevel: u16,
+    pub championPoints: u32,
+    // Milliseconds since the epoch
+    #[serde(default)]
+    pub lastPlayTime: i64,
+    // No longer sent since the 2024 mastery rework
+    #[serde(default)]
+    pub chestGranted: Option<bool>,
+}
+
+#[allow(non_snake_case)]
+#[derive(Deserialize, Debug)]
+pub(crate) struct GameJSON {
+    pub gameMode: String,
+    pub gameType: String,
+    // Custom games have no queue
+    #[serde(default)]
+    pub gameQueueConfigId: u16,
+    #[serde(default)]
+    pub mapId: u16,
+    // Milliseconds since the epoch, 0 while the players are loading in
+    #[serde(default)]
+    pub gameStartTime: i64,
+    // Seconds since the game started
+    #[serde(default)]
+    pub gameLength: i64,
+    pub participants: Vec<ParticipantJSON>,
+    // Empty in blind pick and ARAM
+    #[serde(default)]
+    pub bannedChampions: Vec<BannedChampionJSON>,
+}
+
+#[allow(non_snake_case)]
+#[derive(Deserialize, Debug)]
+pub(crate) struct BannedChampionJSON {
+    // -1 when the ban was skipped
+    pub championId: i32,
+    pub teamId: u16,
+    pub pickTurn: u8,
+}
+
+#[allow(non_snake_case)]
+#[derive(Deserialize, Debug)]
+pub(crate) struct ParticipantJSON {
+    pub teamId: u16,
+    // Only set in Arena, where players are paired into numbered teams
+    #[serde(default)]
+    pub playerSubteamId: u8,
+    // Bots may have no Riot ID or puuid, either missing or null
+    #[serde(default)]
+    pub riotId: Option<String>,
+    #[serde(default)]
+    pub puuid: Option<String>,
+    pub championId: u16,
+    // The spells on D and F
+    #[serde(default)]
+    pub spell1Id: u16,
+    #[serde(default)]
+    pub spell2Id: u16,
+    #[serde(default)]
+    pub perks: Option<LivePerksJSON>,
+}
+
+#[allow(non_snake_case)]
+#[derive(Deserialize, Debug)]
+// The runes of a player in a live game, the keystone first
+pub(crate) struct LivePerksJSON {
+    #[serde(default)]
+    pub perkIds: Vec<u16>,
+    #[serde(default)]
+    pub perkSubStyle: u16,
+}
+
+impl ParticipantJSON {
+    pub fn riot_id(&self) -> &str {
+        self.riotId.as_deref().unwrap_or("")
+    }
+
+    // Empty for bots
+    pub fn puuid(&self) -> &str {
+        self.puuid.as_deref().unwrap_or("")
+    }
+
+    pub fn keystone(&self) -> Option<u16> {
+        self.perks.as_ref()?.perkIds.first().copied()
+    }
+
+    pub fn secondary_tree(&self) -> Option<u16> {
+        self.perks.as_ref().map(|p| p.perkSubStyle)
+    }
+}
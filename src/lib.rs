@@ -0,0 +1,25 @@
+/// This file contains the public API of the lolookup library
+mod api;
+pub mod cache;
+pub mod champ;
+mod client;
+mod ddragon;
+mod error;
+pub mod items;
+mod json;
+mod models;
+pub mod queue;
+pub mod rank;
+pub mod region;
+pub mod runes;
+pub mod spells;
+
+pub use api::Client;
+pub use cache::Cache;
+pub use client::RetryPolicy;
+pub use error::{ProgramError, Result};
+pub use models::*;
+pub use region::{Cluster, Region, REGIONS};
+
+// The environment variable the Riot API key is read from
+pub const API_KEY_ENV: &str = "RIOT_API_KEY";
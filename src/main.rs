@@ -3,797 +3,446 @@ extern crate reqwest;
 extern crate serde;
 extern crate serde_json;
 
-mod champ;
-
-use champ::champion_map;
-use console::{Style, StyledObject};
-use futures::future::{join, join_all};
-use reqwest::header::{HeaderMap, HeaderValue};
-use serde::Deserialize;
-use std::collections::HashMap;
+mod cli;
+
+use clap::{ArgMatches, Shell};
+use cli::app::{self, BIN_NAME};
+use cli::config::Config;
+use cli::output::{self, OutputFormat};
+use console::Term;
+use lolookup::champ::Champions;
+use lolookup::items::Items;
+use lolookup::queue::{self, Queues};
+use lolookup::runes::Runes;
+use lolookup::spells::Spells;
+use lolookup::{
+    cache, Account, Cache, ChampionPool, Client, Game, HistoryFilter, PlayerMasteries,
+    ProgramError, Region, Result, RetryPolicy, UserGames, API_KEY_ENV,
+};
 use std::env;
-use std::fmt;
-use std::result;
-use std::{thread, time};
-use std::convert::From;
-
-const API_KEY: &str = "";
-const X_RIOT_TOKEN: &'static str = "X-Riot-Token";
-const ACC_COLS: [&str; 6] = ["Level", "Rank", "W/L", "LP", "Hot Streak", "Top Role"];
-const GAME_COLS: [&str; 6] = ["Username", "Rank", "LP", "W/L", "Champion", "Hot Streak"];
-const MATCH_HISTORY_COLS: [&str; 4] = ["Role", "Mode", "Champion", "Outcome"];
-const FIRE: &'static str = "🔥";
-const COLD: &'static str = "🧊";
-const DEFAULT_CHAMP: &'static str = "Unknown Champ";
-const SLEEP_DUR: u64 = 300;
-
-type Result<T> = result::Result<T, ProgramError>;
-
-macro_rules! fetch {
-    ($url:expr) => {{
-        let mut headers = HeaderMap::new();
-        headers.insert(X_RIOT_TOKEN, HeaderValue::from_static(API_KEY));
-        let client = reqwest::Client::new();
-        let res = client
-            .get(&$url)
-            .headers(headers)
-            .send()
-            .await
-            .or_else(|_| Err(ProgramError::InvalidUrl));
-        res
-    }};
-}
-
-macro_rules! gameHeader {
-    ($team:expr, $i:ident) => {
-        println!("{:=^81}", $i.apply_to($team));
-        println!(
-            "{0: ^17} | {1: ^6} | {2: ^6} | {3: ^6} | {4: ^20} | {5: ^10}",
-            GAME_COLS[0], GAME_COLS[1], GAME_COLS[2], GAME_COLS[3], GAME_COLS[4], GAME_COLS[5]
-        );
-        println!(
-            "{:-<18}+{:-<8}+{:-<8}+{:-<8}+{:-<22}+{:-<12}",
-            "-", "-", "-", "-", "-", "-"
-        );
-    };
-}
-
-macro_rules! emoji {
-    ($e:expr, $o:expr) => {{
-        match utf8_supported() {
-            true => $e,
-            false => $o,
-        }
-    }};
-}
+use std::io;
+use std::process;
+use std::time::Duration;
+use tokio::time::delay_for;
+
+// Recent games read per player for `game --champion-stats` and `--premades`, kept low as each
+// one is a request
+const LIVE_HISTORY_GAMES: u16 = 10;
+const WATCH_INTERVAL: u64 = 20;
+// The exit status of any command that failed, so scripts can tell it apart from a result
+const ERROR_EXIT_CODE: i32 = 1;
+// `game --watch --follow` exits with this status once the game is over
+const GAME_ENDED_EXIT_CODE: i32 = 3;
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    let args: Vec<String> = env::args().collect();
-    if args.len() < 2 {
-        println!("Invalid args. Type 'help' to more info.");
-        return Ok(());
-    }
-
-    let username = match args.len() {
-        1 | 2 => None,
-        3 => Some(args.get(2).unwrap().clone()),
-        _ => Some(args.iter().skip(2).fold(String::new(), |acc, x| acc + x)),
+    let matches = app::build_app().get_matches();
+    let (command, args) = match matches.subcommand() {
+        (command, Some(args)) => (command, args),
+        _ => return Ok(()),
     };
 
-    match &args[1][..] {
-        "lookup" => {
-            if let Some(username) = username {
-                match look_up_user(&username).await {
-                    Ok(user) => user.display_console(),
-                    Err(e) => println!("{}", e),
-                }
-            } else {
-                println!("Must supply username.");
-                return Ok(());
-            }
+    // Global options are read from the subcommand so they work before or after it
+    let format = args
+        .value_of("format")
+        .and_then(|f| f.parse::<OutputFormat>().ok())
+        .unwrap_or_default();
+
+    // A config that cannot be read is never overwritten. Other commands warn and carry on with
+    // the defaults.
+    let mut config = match Config::load() {
+        Ok(config) => config,
+        Err(e) if matches!(command, "region" | "key") => fail(&e, format),
+        Err(e) => {
+            eprintln!("{}, using the default settings.", e);
+            Config::default()
         }
+    };
+    let region = args
+        .value_of("region")
+        .and_then(|r| r.parse::<Region>().ok())
+        .or(config.region)
+        .unwrap_or_default();
+    let api_key = resolve_api_key(args.value_of("api-key"), &config);
+    let retry = retry_policy(args, &config);
+
+    let key = match &api_key {
+        Ok(key) => key.as_str(),
+        Err(e)
+            if matches!(
+                command,
+                "lookup" | "game" | "history" | "champions" | "mastery" | "match"
+            ) =>
+        {
+            fail(e, format)
+        }
+        Err(_) => "",
+    };
+    let client = Client::with_options(key, region, retry, Cache::new(!args.is_present("no-cache")));
+
+    match command {
+        "lookup" => match client.profile(&riot_id(args)).await {
+            Ok(user) => output::print(&user, format),
+            Err(e) => fail(&e, format),
+        },
         "game" => {
-            if let Some(username) = username {
-                match look_up_game(&username).await {
-                    Ok(game) => game.display_console(),
-                    Err(e) => println!("{}", e),
-                }
-            } else {
-                println!("Must supply username.");
-                return Ok(());
+            let details = LiveDetails {
+                filter: HistoryFilter {
+                    count: args
+                        .value_of("games")
+                        .and_then(|n| n.parse().ok())
+                        .unwrap_or(LIVE_HISTORY_GAMES),
+                    ..HistoryFilter::default()
+                },
+                champion_stats: args.is_present("champion-stats"),
+                premades: args.is_present("premades"),
+            };
+            if args.is_present("watch") {
+                let interval = args
+                    .value_of("interval")
+                    .and_then(|n| n.parse().ok())
+                    .unwrap_or(WATCH_INTERVAL);
+                let watch = Watch {
+                    interval: Duration::from_secs(interval),
+                    follow: args.is_present("follow"),
+                };
+                let username = riot_id(args);
+                return watch_game(&username, &details, watch, &client, format).await;
+            }
+            match look_up_game(&riot_id(args), &details, &client).await {
+                Ok(game) => output::print(&game, format),
+                Err(e) => fail(&e, format),
             }
         }
         "history" => {
-            if let Some(username) = username {
-                match look_up_match_history(&username).await {
-                    Ok(history) => history.display_console(),
-                    Err(e) => println!("{}", e),
-                }
-                return Ok(());
-            } else {
-                println!("Must supply username.");
-                return Ok(());
+            let filter = history_filter(args);
+            let expand = args
+                .value_of("expand")
+                .and_then(|n| n.parse::<usize>().ok());
+            match look_up_match_history(&riot_id(args), &filter, &client).await {
+                Ok(history) => match expand {
+                    Some(row) => expand_row(&history, row, &client, format).await,
+                    None => output::print(&history, format),
+                },
+                Err(e) => fail(&e, format),
             }
         }
-        "help" => {
-            println!("Available commands:");
-            println!("  lookup <username>      => returns account statistics");
-            println!("  game <username>        => returns data about current game");
-            println!("  history <username>     => returns match history");
+        "champions" => {
+            let filter = history_filter(args);
+            let mastery = args.is_present("mastery");
+            match look_up_champions(&riot_id(args), &filter, mastery, &client).await {
+                Ok(pool) => output::print(&pool, format),
+                Err(e) => fail(&e, format),
+            }
         }
-        _ => println!("Invalid argument. Type help to see list of args."),
-    }
-
-    Ok(())
-}
-
-async fn look_up_game(username: &str) -> Result<Game> {
-    let account = get_account(username).await?;
-    let json = get_current_game(&account.id).await?;
-    let game = create_game(&json.participants, &json.gameMode, &json.gameType).await;
-    Ok(game)
-}
-
-async fn create_game(teammates: &Vec<ParticipantJSON>, mode: &str, game_type: &str) -> Game {
-    let futures = teammates
-        .iter()
-        .map(|p| get_account_rank(&p.summonerId))
-        .collect::<Vec<_>>();
-    let result = join_all(futures).await;
-
-    let mut blue: Vec<Participant> = Vec::new();
-    let mut red: Vec<Participant> = Vec::new();
-    for (player, data) in teammates.iter().zip(result.into_iter()) {
-        let rank = data.expect("Error looking up user");
-        if player.teamId == 100 {
-            red.push(Participant::new(false, player.summonerName.clone(), rank, player.championId));
-        } else {
-            blue.push(Participant::new(false, player.summonerName.clone(), rank, player.championId));
+        "mastery" => {
+            let top = args
+                .value_of("top")
+                .and_then(|t| t.parse().ok())
+                .unwrap_or(10);
+            match look_up_mastery(&riot_id(args), top, &client).await {
+                Ok(masteries) => output::print(&masteries, format),
+                Err(e) => fail(&e, format),
+            }
         }
-    }
-    Game {
-        red,
-        blue,
-        mode: mode.to_string(),
-        game_type: game_type.to_string(),
-    }
-}
-
-// Returns the most played role
-async fn get_most_played_role(account_id: &str) -> result::Result<String, ProgramError> {
-    let history = get_history(account_id).await?;
-    let mut map: HashMap<String, i8> = HashMap::new();
-
-    let mut adc = 0;
-    let mut sup = 0;
-    for game in history.matches {
-        if let Some(r) = map.get_mut(&game.lane) {
-            if game.lane == "BOTTOM" {
-                if game.role == "DUO_CARRY" {
-                    adc += 1;
-                } else {
-                    sup += 1;
+        "match" => match client
+            .match_details(args.value_of("id").unwrap_or(""))
+            .await
+        {
+            Ok(details) => output::print(&details, format),
+            Err(e) => fail(&e, format),
+        },
+        "region" => match args.value_of("default") {
+            Some(r) => match r.parse::<Region>() {
+                Ok(region) => {
+                    config.region = Some(region);
+                    match config.save() {
+                        Ok(_) => println!("Default region set to {}.", region),
+                        Err(e) => fail(&e, format),
+                    }
                 }
+                Err(_) => fail(&ProgramError::InvalidRegion(r.to_string()), format),
+            },
+            None => {
+                // The saved default, not a region passed with --region for this run
+                let region = config.region.unwrap_or_default();
+                println!("Default region: {} ({})", region, region.cluster().id())
             }
-            *r += 1;
-        } else {
-            map.insert(game.lane, 1);
-        }
-    }
-
-    let mut max = ("", -1);
-    for (key, value) in map.iter() {
-        if *value > max.1 {
-            max = (key, *value);
-        }
-    }
-
-    match max.0 {
-        "BOTTOM" if adc > sup => Ok(String::from("ADC")),
-        "BOTTOM" => Ok(String::from("SUPPORT")),
-        _ => Ok(String::from(max.0)),
-    }
-}
-
-async fn get_history(account_id: &str) -> Result<HistoryJSON> {
-    let url = format!("https://na1.api.riotgames.com/lol/match/v4/matchlists/by-account/{}?queue=400&queue=410&queue=420&queue=430&queue=440&endIndex=20", account_id);
-    let res = fetch!(url)?;
-    match res.status().as_u16() {
-        404 => Err(ProgramError::NoHistory),
-        200 => {
-            let data = res
-                .text()
-                .await
-                .or_else(|_| Err(ProgramError::DeserializeError))?;
-            Ok(serde_json::from_str(&data[..]).unwrap()) //.or_else(|_| Err(ProgramError::DeserializeError))?)
-        }
-        _ => {
-            println!("{}", res.status().as_u16());
-            return Err(ProgramError::InvalidResponse);
-        }
-    }
-}
-
-async fn look_up_user(username: &str) -> Result<UserAccount> {
-    let account = get_account(username).await?;
-    let rank = get_account_rank(&account.id);
-    let role = get_most_played_role(&account.accountId);
-    let result = join(rank, role).await;
-    if let (Ok(rank), Ok(role)) = result {
-        return Ok(UserAccount::new(account, rank, role));
-    }
-    Err(ProgramError::InvalidResponse)
-}
-
-async fn get_current_game(summoner_id: &str) -> result::Result<GameJSON, ProgramError> {
-    let url =
-        String::from("https://na1.api.riotgames.com/lol/spectator/v4/active-games/by-summoner/")
-            + summoner_id;
-    let res = fetch!(url)?;
-    return match res.status().as_u16() {
-        404 => Err(ProgramError::NotInGame),
-        200 => {
-            let data = res
-                .text()
-                .await
-                .or_else(|_| Err(ProgramError::DeserializeError))?;
-            Ok(serde_json::from_str(&data[..]).or_else(|_| Err(ProgramError::DeserializeError))?)
-        }
-        _ => Err(ProgramError::InvalidResponse),
-    };
-}
-
-fn determine_role(role: &str, lane: &str) -> String {
-    match lane {
-        "BOTTOM" => match role {
-            "DUO_SUPPORT" => "SUPPORT".to_string(),
-            _ => "ADC".to_string(),
         },
-        _ => lane.to_string(),
-    }
-}
-
-// retrieves the match data for a given id
-async fn get_match_data(match_id: u64) -> Result<MatchDataJSON> {
-    let url =
-        String::from("https://na1.api.riotgames.com/lol/match/v4/matches/") + &match_id.to_string();
-    let res = fetch!(url)?;
-
-    match res.status().as_u16() {
-        200 => {
-            let data = res
-                .text()
-                .await
-                .or_else(|_| Err(ProgramError::DeserializeError))?;
-            let match_data: MatchDataJSON =
-                serde_json::from_str(&data[..]).or_else(|_| Err(ProgramError::DeserializeError))?;
-            Ok(match_data)
+        "key" => {
+            config.api_key = args.value_of("key").map(|k| k.to_string());
+            match config.save() {
+                Ok(_) => println!("API key saved."),
+                Err(e) => fail(&e, format),
+            }
         }
-        404 => Err(ProgramError::InvalidAccount),
-        _ => Err(ProgramError::BadResponse),
-    }
-}
-
-async fn look_up_match_history(username: &str) -> Result<UserGames> {
-    let account = get_account(username).await?;
-    let history = get_history(&account.accountId).await?;
-    // Need to pause the main thread so the API key usage does not exceed the limit
-    let sleep_time = time::Duration::from_millis(SLEEP_DUR);
-    thread::sleep(sleep_time);
-    let games = history
-        .matches
-        .iter()
-        .map(|m| get_match_data(m.gameId))
-        .collect::<Vec<_>>();
-    let result = join_all(games).await;
-
-    let mut recent_games = Vec::new();
-    for (g, m) in history.matches.iter().zip(result.into_iter()) {
-        if let Ok(m) = m {
-            let is_win = match &m.teams[0].win[..] {
-                "Win" => true,
-                _ => false,
-            };
-
-            //see if it was a win for the user.
-            let par_pos = m
-                .participantIdentities
-                .iter()
-                .position(|p| p.player.accountId == account.accountId)
-                .unwrap();
-            let id = m.participantIdentities[par_pos].participantId;
-            let mut res = false; // Result of the user winning or losing
-            for p in m.participants {
-                if p.participantId == id {
-                    res = match p.teamId {
-                        100 => is_win,
-                        _ => !is_win,
-                    };
-                    break;
+        "data" => match args.subcommand() {
+            ("refresh", Some(sub)) => match refresh_static_data(sub.value_of("patch")).await {
+                Ok(version) => {
+                    println!(
+                        "Champion, rune, item and spell data updated to patch {}.",
+                        version
+                    )
+                }
+                Err(e) => fail(&e, format),
+            },
+            ("queues", Some(_)) => print_queues(&Queues::load().await),
+            ("champion", Some(sub)) => {
+                let query = join_values(sub, "name");
+                match Champions::load().await.find(&query) {
+                    Some(c) => {
+                        println!("{} ({})", c.name, c.key);
+                        if !c.title.is_empty() {
+                            println!("Title: {}", c.title);
+                        }
+                        if !c.tags.is_empty() {
+                            println!("Tags: {}", c.tags.join(", "));
+                        }
+                    }
+                    None => println!("Unknown champion '{}'.", query),
                 }
             }
-            recent_games.push(UserMatch::new(
-                determine_role(&g.role, &g.lane),
-                g.queue,
-                g.champion,
-                Some(res),
-            ))
-        } else {
-            recent_games.push(UserMatch::new(
-                determine_role(&g.role, &g.lane),
-                g.queue,
-                g.champion,
-                None,
-            ))
-        }
-    }
-    // let recent_games: Vec<UserMatch> = history.matches.into_iter()
-    //     .map(|game| UserMatch::new(determine_role(&game.role, &game.lane), game.queue, game.champion, res))
-    //     .collect();
-    Ok(UserGames {
-        games: recent_games,
-        username: String::from(username),
-    })
-}
-
-async fn get_account_rank(summoner_id: &str) -> Result<Rank> {
-    let url = String::from("https://na1.api.riotgames.com/lol/league/v4/entries/by-summoner/")
-        + summoner_id;
-    let res = fetch!(url)?;
-
-    match res.status().as_u16() {
-        200 => {
-            let data = res
-                .text()
-                .await
-                .or_else(|_| Err(ProgramError::DeserializeError))?;
-            let rank: Vec<Rank> =
-                serde_json::from_str(&data[..]).or_else(|_| Err(ProgramError::DeserializeError))?;
-            if let Some(x) = rank
-                .into_iter()
-                .filter(|v| v.queueType == "RANKED_SOLO_5x5")
-                .collect::<Vec<Rank>>()
-                .get(0)
-            {
-                Ok(x.clone())
-            } else {
-                Ok(Rank::unranked())
+            _ => {
+                let champs = Champions::load().await;
+                println!(
+                    "Champion data: patch {} ({} champions)",
+                    champs.version,
+                    champs.len()
+                );
             }
-        }
-        404 => Err(ProgramError::InvalidAccount),
-        _ => Err(ProgramError::BadResponse),
-    }
-}
-
-async fn get_account(username: &str) -> result::Result<Account, ProgramError> {
-    let url =
-        String::from("https://na1.api.riotgames.com/lol/summoner/v4/summoners/by-name/") + username;
-    let res = fetch!(url)?;
-
-    match res.status().as_u16() {
-        200 => {
-            let data = res
-                .text()
-                .await
-                .or_else(|_| Err(ProgramError::DeserializeError))?;
-            Ok(serde_json::from_str(&data[..]).or_else(|_| Err(ProgramError::DeserializeError))?)
-        }
-        404 => Err(ProgramError::InvalidAccount),
-        _ => Err(ProgramError::BadResponse),
-    }
-}
-
-fn utf8_supported() -> bool {
-    match std::env::var("LANG") {
-        Ok(lang) => lang.to_uppercase().ends_with("UTF-8"),
-        _ => false,
-    }
-}
-
-fn format_game_id(id: u16) -> String {
-    match id {
-        400 => "Normal Draft".to_string(),
-        410 => "Ranked Dynamic".to_string(),
-        420 => "Ranked Solo".to_string(),
-        430 => "Blink Pick".to_string(),
-        440 => "Ranked Flex".to_string(),
-        _ => "Unknown".to_string(),
-    }
-}
-
-#[derive(Debug)]
-pub enum ProgramError {
-    DeserializeError,
-    InvalidUrl,
-    NotInGame,
-    NotRanked,
-    InvalidResponse,
-    InvalidAccount,
-    BadResponse,
-    NoHistory,
-}
-
-impl fmt::Display for ProgramError {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self {
-            ProgramError::NotInGame => write!(f, "Summoner is not in game."),
-            ProgramError::InvalidAccount => write!(f, "Account does not exist."),
-            ProgramError::InvalidUrl => write!(f, "Invalid url"),
-            ProgramError::NotRanked => write!(f, "Summoner is not ranked in solo's"),
-            ProgramError::BadResponse => write!(f, "Bad response"),
-            ProgramError::DeserializeError => write!(f, "Error deserializing JSON"),
-            ProgramError::InvalidResponse => {
-                write!(f, "Invalid response, Status code not 404 or 200")
+        },
+        "cache" => {
+            if let ("clear", Some(_)) = args.subcommand() {
+                match cache::clear() {
+                    Ok(_) => println!("Cache cleared."),
+                    Err(_) => {
+                        println!("Unable to clear the cache.");
+                        process::exit(ERROR_EXIT_CODE);
+                    }
+                }
             }
-            ProgramError::NoHistory => write!(f, "No history available"),
         }
-    }
-}
-
-// Wrapper struct to display the user games
-struct UserGames {
-    username: String,
-    games: Vec<UserMatch>,
-}
-
-impl UserGames {
-    fn display_console(&self) -> () {
-        let yellow: Style = Style::new().yellow();
-        let label = format!(" {} Match History ", &self.username);
-        let map = champion_map();
-        let (wins, losses) = self.games.iter()
-            .fold((0,0), |(x,y), game| {
-                if let Some(g) = game.outcome {
-                    return if g {
-                        (x + 1, y)
-                    } else {
-                        (x, y + 1)
-                    };
-                }
-                (x, y)
-            });
-        println!("{:=^68}", yellow.apply_to(&label));
-        println!("Last 20 games stats:");
-        println!("Total wins: {}", wins);
-        println!("Total losses: {}", losses);
-        println!("W/L Ratio: {:.2}%", (wins as f32 / 20.0) * 100.0);
-        println!(
-            "{0: ^10} | {1: ^15} | {2: ^20} | {3: ^15}",
-            MATCH_HISTORY_COLS[0],
-            MATCH_HISTORY_COLS[1],
-            MATCH_HISTORY_COLS[2],
-            MATCH_HISTORY_COLS[3]
-        );
-        println!("{:-<11}+{:-<17}+{:-<22}+{:-<16}", "-", "-", "-", "-");
-        for game in &self.games {
-            Self::display_row(game, &map);
+        "completions" => {
+            if let Some(shell) = args.value_of("shell").and_then(|s| s.parse::<Shell>().ok()) {
+                app::build_app().gen_completions_to(BIN_NAME, shell, &mut io::stdout());
+            }
         }
+        _ => {}
     }
 
-    fn display_row(game: &UserMatch, map: &HashMap<u16, String>) {
-        let temp = String::from(DEFAULT_CHAMP);
-        let champ = map.get(&game.champ).unwrap_or_else(|| &temp);
-        println!(
-            "{0: ^10} | {1: ^15} | {2: ^20} | {3: ^15}",
-            game.role,
-            format_game_id(game.game_mode),
-            champ,
-            game.get_outcome()
-        );
-    }
-}
-
-#[allow(non_snake_case)]
-#[derive(Deserialize, Debug)]
-// Represents a match fetch given the match id
-struct MatchDataJSON {
-    teams: Vec<TeamJSON>,
-    participants: Vec<MatchParticipantJSON>,
-    participantIdentities: Vec<ParticipantIdentityJSON>,
-}
-
-#[allow(non_snake_case)]
-#[derive(Deserialize, Debug)]
-struct TeamJSON {
-    teamId: u16,
-    win: String,
-}
-
-#[allow(non_snake_case)]
-#[derive(Deserialize, Debug)]
-struct MatchParticipantJSON {
-    participantId: u16,
-    teamId: u16,
+    Ok(())
 }
 
-#[allow(non_snake_case)]
-#[derive(Deserialize, Debug)]
-struct ParticipantIdentityJSON {
-    participantId: u16,
-    player: PlayerJSON,
+// Prints the error in the chosen format and exits with ERROR_EXIT_CODE
+fn fail(e: &ProgramError, format: OutputFormat) -> ! {
+    output::print_error(e, format);
+    process::exit(ERROR_EXIT_CODE);
 }
 
-#[allow(non_snake_case)]
-#[derive(Deserialize, Debug)]
-struct PlayerJSON {
-    accountId: String,
-    summonerName: String,
-    summonerId: String,
+// A Riot ID typed as several words is joined back together with spaces
+fn riot_id(args: &ArgMatches) -> String {
+    join_values(args, "name")
 }
 
-#[derive(Debug)]
-// A single game that the user played in
-struct UserMatch {
-    role: String,
-    game_mode: u16,
-    champ: u16,
-    outcome: Option<bool>, // true if a win
+fn join_values(args: &ArgMatches, name: &str) -> String {
+    args.values_of(name)
+        .map(|values| values.collect::<Vec<_>>().join(" "))
+        .unwrap_or_default()
 }
 
-impl UserMatch {
-    //TODO: Finish implementation
-    fn new(role: String, mode: u16, champ: u16, outcome: Option<bool>) -> Self {
-        UserMatch {
-            role: role,
-            game_mode: mode,
-            champ: champ,
-            outcome: outcome,
-        }
+fn history_filter(args: &ArgMatches) -> HistoryFilter {
+    let mut filter = HistoryFilter::default();
+    if let Some(count) = args.value_of("count").and_then(|c| c.parse().ok()) {
+        filter.count = count;
     }
-
-    fn get_outcome(&self) -> &str {
-        match self.outcome {
-            Some(v) => match v {
-                true => "Win",
-                _ => "Loss",
-            },
-            None => "Unavaliable",
-        }
+    if let Some(queues) = args.values_of("queue") {
+        filter.queues = queues.filter_map(queue::queue_id).collect();
     }
-}
-
-#[derive(Debug)]
-struct UserAccount {
-    account: Account,
-    rank: Rank,
-    top_role: String,
-}
-
-impl UserAccount {
-    fn new(account: Account, rank: Rank, top_role: String) -> Self {
-        UserAccount {
-            account,
-            rank,
-            top_role,
-        }
+    filter.since = args.value_of("since").and_then(app::parse_date);
+    filter
+}
+
+// The --api-key flag wins over the RIOT_API_KEY env variable, which wins over the config file
+fn resolve_api_key(flag: Option<&str>, config: &Config) -> Result<String> {
+    flag.map(|key| key.to_string())
+        .or_else(|| env::var(API_KEY_ENV).ok())
+        .or_else(|| config.api_key.clone())
+        .filter(|key| !key.trim().is_empty())
+        .map(|key| key.trim().to_string())
+        .ok_or(ProgramError::MissingApiKey)
+}
+
+// Flags take precedence over the values stored in the config file. Both are validated by clap.
+fn retry_policy(args: &ArgMatches, config: &Config) -> RetryPolicy {
+    let mut policy = RetryPolicy::default();
+    let retries = args.value_of("retries").and_then(|r| r.parse().ok());
+    if let Some(retries) = retries.or(config.retries) {
+        policy.retries = retries;
     }
-
-    fn display_console(&self) {
-        let yellow: Style = Style::new().yellow();
-        println!("{:=^58}", yellow.apply_to(&self.account.name));
-        println!(
-            "{0: ^6} | {1: ^6} | {2: ^6} | {3: ^6} | {4: ^10} | {5: ^10}",
-            ACC_COLS[0], ACC_COLS[1], ACC_COLS[2], ACC_COLS[3], ACC_COLS[4], ACC_COLS[5]
-        );
-        println!(
-            "{:-<7}+{:-<8}+{:-<8}+{:-<8}+{:-<12}+{:-<10}",
-            "-", "-", "-", "-", "-", "-"
-        );
-        if utf8_supported() {
-            println!(
-                "{0: ^6} | {1: ^6} | {2: ^6} | {3: ^6} | {4: ^9} | {5: ^10}",
-                self.account.summonerLevel,
-                self.rank.print_rank(),
-                self.rank.style_wl(),
-                self.rank.leaguePoints,
-                self.rank.display_streak(),
-                self.top_role
-            );
-        } else {
-            println!(
-                "{0: ^6} | {1: ^6} | {2: ^6} | {3: ^6} | {4: ^10} | {5: ^10}",
-                self.account.summonerLevel,
-                self.rank.print_rank(),
-                self.rank.style_wl(),
-                self.rank.leaguePoints,
-                self.rank.display_streak(),
-                self.top_role
-            );
-        }
+    let timeout = args.value_of("timeout").and_then(|t| t.parse::<u64>().ok());
+    if let Some(timeout) = timeout.or(config.timeout) {
+        policy.timeout = Duration::from_secs(timeout.max(1));
     }
+    policy
 }
 
-#[allow(non_snake_case)]
-#[derive(Deserialize, Debug)]
-struct Account {
-    id: String,
-    accountId: String,
-    puuid: String,
-    name: String,
-    profileIconId: i32,
-    revisionDate: i64,
-    summonerLevel: i32,
-}
-
-#[allow(non_snake_case)]
-#[derive(Deserialize, Debug)]
-struct ParticipantJSON {
-    teamId: i32,
-    summonerName: String,
-    summonerId: String,
-    championId: u16,
+async fn look_up_game(username: &str, details: &LiveDetails, client: &Client) -> Result<Game> {
+    let account = client.summoner(username).await?;
+    live_game(&account, details, client).await
 }
 
-#[allow(non_snake_case)]
-#[derive(Debug)]
-struct Participant {
-    team: bool,
-    summonerName: String,
-    championId: u16,
-    rank: Rank,
+// What `game` looks up beyond the spectator data. Both options read the recent games of
+// every player, so they share one set of match ids.
+struct LiveDetails {
+    filter: HistoryFilter,
+    champion_stats: bool,
+    premades: bool,
 }
 
-impl Participant {
-    fn new(team: bool, name: String, rank: Rank, champ_id: u16) -> Self {
-        Participant {
-            team,
-            summonerName: name,
-            championId: champ_id,
-            rank,
+async fn live_game(account: &Account, details: &LiveDetails, client: &Client) -> Result<Game> {
+    let mut game = client.active_game(account).await?;
+    match (details.champion_stats, details.premades) {
+        (true, premades) => {
+            client
+                .live_champion_stats(&mut game, &details.filter, premades)
+                .await
         }
+        (false, true) => client.premades(&mut game, &details.filter).await,
+        (false, false) => {}
     }
+    Ok(game)
 }
 
-#[allow(non_snake_case)]
-#[derive(Deserialize, Debug)]
-struct HistoryJSON {
-    matches: Vec<MatchJSON>,
+// How `game --watch` polls the spectator endpoint
+struct Watch {
+    interval: Duration,
+    // Keep refreshing while the game is on instead of stopping at the first table
+    follow: bool,
+}
+
+// Checks every interval until the player is loading into a game and shows it. When following,
+// the table is redrawn until the game ends and the process exits with GAME_ENDED_EXIT_CODE.
+// Status messages go to stderr so JSON output stays parseable.
+async fn watch_game(
+    username: &str,
+    details: &LiveDetails,
+    watch: Watch,
+    client: &Client,
+    format: OutputFormat,
+) -> Result<()> {
+    let account = match client.summoner(username).await {
+        Ok(account) => account,
+        Err(e) => fail(&e, format),
+    };
+    let term = Term::stdout();
+    let mut in_game = false;
+    let mut waiting = false;
+    loop {
+        match live_game(&account, details, client).await {
+            Ok(game) => {
+                if in_game && format == OutputFormat::Table && term.features().is_attended() {
+                    let _ = term.clear_screen();
+                }
+                output::print(&game, format);
+                if !watch.follow {
+                    return Ok(());
+                }
+                in_game = true;
+            }
+            Err(ProgramError::NotInGame) if in_game => {
+                eprintln!("{}'s game has ended.", account.name);
+                process::exit(GAME_ENDED_EXIT_CODE);
+            }
+            Err(ProgramError::NotInGame) => {
+                if !waiting {
+                    eprintln!(
+                        "Waiting for {} to start a game, checking every {} seconds...",
+                        account.name,
+                        watch.interval.as_secs()
+                    );
+                    waiting = true;
+                }
+            }
+            // A network hiccup or rate limit should not end a long watch
+            Err(e @ ProgramError::RequestFailed(..)) | Err(e @ ProgramError::RateLimited) => {
+                eprintln!("{}", e);
+            }
+            Err(e) => fail(&e, format),
+        }
+        delay_for(watch.interval).await;
+    }
 }
 
-#[allow(non_snake_case)]
-#[derive(Deserialize, Debug)]
-struct MatchJSON {
-    queue: u16,
-    season: u8,
-    role: String,
-    lane: String,
-    champion: u16,
-    gameId: u64,
+async fn look_up_match_history(
+    username: &str,
+    filter: &HistoryFilter,
+    client: &Client,
+) -> Result<UserGames> {
+    let account = client.summoner(username).await?;
+    client.match_history(&account, filter).await
 }
 
-#[allow(non_snake_case)]
-#[derive(Deserialize, Debug)]
-struct GameJSON {
-    gameMode: String,
-    gameType: String,
-    participants: Vec<ParticipantJSON>,
+async fn look_up_champions(
+    username: &str,
+    filter: &HistoryFilter,
+    mastery: bool,
+    client: &Client,
+) -> Result<ChampionPool> {
+    let account = client.summoner(username).await?;
+    client.champion_pool(&account, filter, mastery).await
 }
 
-#[derive(Debug)]
-struct Game {
-    red: Vec<Participant>,
-    blue: Vec<Participant>,
-    mode: String,
-    game_type: String,
+async fn look_up_mastery(username: &str, top: usize, client: &Client) -> Result<PlayerMasteries> {
+    let account = client.summoner(username).await?;
+    client.top_masteries(&account, top).await
 }
 
-impl Game {
-    fn display_console(&self) {
-        let champ_map = champ::champion_map();
-        let rank_map = champ::rank_map();
-        let red: Style = Style::new().red();
-        let cyan: Style = Style::new().cyan();
-        let avg_red = self.red.iter().fold(0, |acc, v| {
-            acc + rank_map.get(&v.rank.print_rank()).unwrap()
-        }) / 5;
-        let avg_blue = self.blue.iter().fold(0, |acc, v| {
-            acc + rank_map.get(&v.rank.print_rank()).unwrap()
-        }) / 5;
-        let mut blue_rank = "N/A";
-        let mut red_rank = "N/A";
-        for (key, val) in rank_map.iter() {
-            if *val == avg_red {
-                red_rank = key;
-            }
-            if *val == avg_blue {
-                blue_rank = key;
-            }
-        }
-
-        println!("Game Mode: {}", self.mode); // Ranked: CLASSIC MATCHED_GAME    ARAM MATCHED_GAME
-        println!("Game Type: {}", self.game_type);
-        println!("Avg Team Rank: {}", red_rank);
-        gameHeader!("Red Team", red);
-        for person in &self.red {
-            Self::display_row(&person, &champ_map);
-        }
-        println!("\n");
-        println!("Avg Team Rank: {}", blue_rank);
-        gameHeader!("Blue Team", cyan);
-        for person in &self.blue {
-            Self::display_row(&person, &champ_map);
+// Prints the scoreboard of a history row, numbered from 1. The table format shows the history first.
+async fn expand_row(history: &UserGames, row: usize, client: &Client, format: OutputFormat) {
+    let game = match history.games.get(row.wrapping_sub(1)) {
+        Some(game) => game,
+        None => {
+            let msg = format!(
+                "--expand {} is out of range, the history has {} games",
+                row,
+                history.games.len()
+            );
+            fail(&ProgramError::InvalidArgument(msg), format)
         }
+    };
+    if format == OutputFormat::Table {
+        output::print(history, format);
+        println!();
     }
-
-    fn display_row(p: &Participant, map: &HashMap<u16, String>) {
-        println!(
-            "{0: <17} | {1: ^6} | {2: ^6} | {3: ^6} | {4: ^20} | {5}",
-            p.summonerName,
-            p.rank.print_rank(),
-            p.rank.leaguePoints,
-            p.rank.style_wl(),
-            map.get(&p.championId).unwrap(),
-            p.rank.display_streak(),
-        );
+    match client.match_details(&game.match_id).await {
+        Ok(details) => output::print(&details, format),
+        Err(e) => fail(&e, format),
     }
 }
 
-#[allow(non_snake_case)]
-#[derive(Deserialize, Debug, Clone)]
-struct Rank {
-    tier: String,
-    rank: String,
-    queueType: String,
-    wins: i32,
-    losses: i32,
-    hotStreak: bool,
-    leaguePoints: i16,
-}
-impl Rank {
-    fn unranked() -> Self {
-        Rank {
-            tier: String::from("N/A"),
-            rank: String::from("N/A"),
-            queueType: String::from("N/A"),
-            wins: -1,
-            losses: -1,
-            hotStreak: false,
-            leaguePoints: -1,
-        }
-    }
+// Downloads the champion, rune, item and summoner spell data of the same patch along with the
+// queue list, and returns the patch
+async fn refresh_static_data(version: Option<&str>) -> Result<String> {
+    let champs = Champions::refresh(version).await?;
+    Runes::refresh(Some(&champs.version)).await?;
+    Items::refresh(Some(&champs.version)).await?;
+    Spells::refresh(Some(&champs.version)).await?;
+    // Queues are not tied to a patch
+    Queues::refresh().await?;
+    Ok(champs.version)
 }
 
-impl Rank {
-    fn get_wl_ratio(&self) -> f32 {
-        if self.wins == -1 {
-            return -1.0;
-        }
-        let temp = self.wins as f32 / (self.wins as f32 + self.losses as f32);
-        temp * 100.0
+fn print_queues(queues: &Queues) {
+    println!("{: >5} | {: <40} | Map", "Id", "Queue");
+    for q in queues.all() {
+        println!("{: >5} | {: <40} | {}", q.id, q.name, q.map);
     }
-
-    fn style_wl(&self) -> StyledObject<String> {
-        let temp = self.get_wl_ratio();
-        let red = Style::new().red();
-        let green = Style::new().green();
-        let default = Style::new();
-        match temp {
-            temp if temp == -1.0 => default.apply_to("N/A".to_string()),
-            temp if temp > 55.0 => green.apply_to(format!("{0:.2}%", temp)),
-            temp if temp < 48.0 => red.apply_to(format!("{0:.2}%", temp)),
-            _ => default.apply_to(format!("{0:.2}%", temp)),
-        }
-    }
-
-    fn print_rank(&self) -> String {
-        match &self.tier[..] {
-            "N/A" => self.tier.clone(),
-            "CHALLENGER" => "CHAL".to_string(),
-            "GRANDMASTER" => "GRAND".to_string(),
-            "MASTER" => "MAST".to_string(),
-            _ => {
-                let first_char = self.tier.get(0..1).unwrap();
-                first_char.to_string() + "_" + &self.rank
-            }
+    let modes = queues.modes();
+    if !modes.is_empty() {
+        println!();
+        println!("{: <16} | Description", "Game mode");
+        for (mode, description) in modes {
+            println!("{: <16} | {}", mode, description);
         }
     }
-
-    fn display_streak(&self) -> &str {
-        if self.hotStreak {
-            emoji!(FIRE, "Y")
-        } else {
-            emoji!(COLD, "N")
-        }
-    }
-}
\ No newline at end of file
+}
@@ -0,0 +1,525 @@
+/// This file contains the public models returned by the lookups
+use crate::rank::{Division, RankOrdinal, RankedQueue, Tier};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+// A summoner on the client's region together with its Riot ID
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Account {
+    pub puuid: String,
+    #[serde(rename(deserialize = "summonerLevel"))]
+    pub summoner_level: i32,
+    // Filled in from the Riot ID, the summoner endpoint no longer returns a name
+    #[serde(default)]
+    pub name: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct UserAccount {
+    pub account: Account,
+    // One entry per ranked queue the player is placed in, solo queue first
+    pub ranks: Vec<Rank>,
+    pub top_role: String,
+    // The champions with the most mastery points, highest first
+    pub top_masteries: Vec<Mastery>,
+}
+
+impl UserAccount {
+    pub fn new(
+        account: Account,
+        ranks: Vec<Rank>,
+        top_role: String,
+        top_masteries: Vec<Mastery>,
+    ) -> Self {
+        UserAccount {
+            account,
+            ranks,
+            top_role,
+            top_masteries,
+        }
+    }
+}
+
+// Which games a match history lookup returns
+#[derive(Debug, Clone)]
+pub struct HistoryFilter {
+    pub count: u16,
+    // Queue ids to include, e.g. 420 for ranked solo. Empty means every queue.
+    pub queues: Vec<u16>,
+    // Only games started after this unix timestamp, in seconds
+    pub since: Option<i64>,
+}
+
+impl Default for HistoryFilter {
+    fn default() -> Self {
+        HistoryFilter {
+            count: 20,
+            queues: Vec::new(),
+            since: None,
+        }
+    }
+}
+
+// Wrapper struct to display the user games
+#[derive(Serialize, Debug)]
+pub struct UserGames {
+    pub username: String,
+    pub games: Vec<UserMatch>,
+}
+
+impl UserGames {
+    pub fn wins(&self) -> usize {
+        self.games.iter().filter(|g| g.win == Some(true)).count()
+    }
+
+    pub fn losses(&self) -> usize {
+        self.games.iter().filter(|g| g.win == Some(false)).count()
+    }
+
+    // Averages a stat over the games that were not remade, None when every game was a remake
+    pub fn average<F: Fn(&UserMatch) -> f32>(&self, stat: F) -> Option<f32> {
+        let played: Vec<&UserMatch> = self.games.iter().filter(|g| g.win.is_some()).collect();
+        match played.len() {
+            0 => None,
+            n => Some(played.iter().map(|g| stat(g)).sum::<f32>() / n as f32),
+        }
+    }
+
+    // Win rate in percent over the games with a winner, None when every game was a remake
+    pub fn win_rate(&self) -> Option<f32> {
+        let played = self.wins() + self.losses();
+        match played {
+            0 => None,
+            _ => Some(self.wins() as f32 / played as f32 * 100.0),
+        }
+    }
+}
+
+// A single game that the user played in
+#[derive(Serialize, Debug)]
+pub struct UserMatch {
+    pub match_id: String,
+    pub role: String,
+    pub queue_id: u16,
+    pub queue: String,
+    pub champion_id: u16,
+    pub champion: String,
+    pub win: Option<bool>, // None for a remake
+    pub kills: u16,
+    pub deaths: u16,
+    pub assists: u16,
+    pub cs_per_min: f32,
+    // Share of the team's kills the player took part in, in percent
+    pub kill_participation: f32,
+    // Share of the team's damage to champions, in percent
+    pub damage_share: f32,
+    // Seconds
+    pub duration: u32,
+}
+
+impl UserMatch {
+    pub fn get_outcome(&self) -> &str {
+        match self.win {
+            Some(true) => "Win",
+            Some(false) => "Loss",
+            None => "Remake",
+        }
+    }
+
+    // (kills + assists) / deaths, counting a deathless game as one death
+    pub fn kda_ratio(&self) -> f32 {
+        (self.kills + self.assists) as f32 / self.deaths.max(1) as f32
+    }
+}
+
+// A player's results on one champion over their recent games
+#[derive(Serialize, Debug)]
+pub struct ChampionStats {
+    pub champion_id: u16,
+    pub champion: String,
+    // Remakes are not counted
+    pub games: u32,
+    pub wins: u32,
+    pub losses: u32,
+    pub kills: f32,
+    pub deaths: f32,
+    pub assists: f32,
+    pub cs_per_min: f32,
+    pub mastery: Option<Mastery>,
+}
+
+impl ChampionStats {
+    pub fn new(champion_id: u16, champion: String) -> Self {
+        ChampionStats {
+            champion_id,
+            champion,
+            games: 0,
+            wins: 0,
+            losses: 0,
+            kills: 0.0,
+            deaths: 0.0,
+            assists: 0.0,
+            cs_per_min: 0.0,
+            mastery: None,
+        }
+    }
+
+    pub fn win_rate(&self) -> f32 {
+        match self.games {
+            0 => 0.0,
+            n => self.wins as f32 / n as f32 * 100.0,
+        }
+    }
+
+    pub fn kda_ratio(&self) -> f32 {
+        (self.kills + self.assists) / self.deaths.max(1.0)
+    }
+}
+
+// The champions a player played recently, most played first
+#[derive(Serialize, Debug)]
+pub struct ChampionPool {
+    pub username: String,
+    pub champions: Vec<ChampionStats>,
+}
+
+impl ChampionPool {
+    // Groups the games by champion and averages the stats of each one
+    pub fn from_games(history: &UserGames) -> Self {
+        let mut champions: Vec<ChampionStats> = Vec::new();
+        for game in history.games.iter().filter(|g| g.win.is_some()) {
+            let stats = match champions
+                .iter_mut()
+                .position(|c| c.champion_id == game.champion_id)
+            {
+                Some(i) => &mut champions[i],
+                None => {
+                    champions.push(ChampionStats::new(game.champion_id, game.champion.clone()));
+                    champions.last_mut().unwrap()
+                }
+            };
+            // Keep running averages so each field is ready to serialise
+            let n = stats.games as f32;
+            let average = |avg: f32, value: f32| (avg * n + value) / (n + 1.0);
+            stats.kills = average(stats.kills, game.kills as f32);
+            stats.deaths = average(stats.deaths, game.deaths as f32);
+            stats.assists = average(stats.assists, game.assists as f32);
+            stats.cs_per_min = average(stats.cs_per_min, game.cs_per_min);
+            stats.games += 1;
+            match game.win {
+                Some(true) => stats.wins += 1,
+                _ => stats.losses += 1,
+            }
+        }
+        champions.sort_by(|a, b| {
+            b.games
+                .cmp(&a.games)
+                .then(b.wins.cmp(&a.wins))
+                .then(a.champion.cmp(&b.champion))
+        });
+        ChampionPool {
+            username: history.username.clone(),
+            champions,
+        }
+    }
+
+    // Attaches each champion's mastery
+    pub fn merge_mastery(&mut self, masteries: &[Mastery]) {
+        for stats in self.champions.iter_mut() {
+            stats.mastery = masteries
+                .iter()
+                .find(|m| m.champion_id == stats.champion_id)
+                .cloned();
+        }
+    }
+}
+
+// A player's champion masteries, highest points first
+#[derive(Serialize, Debug)]
+pub struct PlayerMasteries {
+    pub username: String,
+    pub masteries: Vec<Mastery>,
+}
+
+// A player's mastery of a champion
+#[derive(Serialize, Debug, Clone)]
+pub struct Mastery {
+    pub champion_id: u16,
+    pub champion: String,
+    pub level: u16,
+    pub points: u32,
+    // Milliseconds since the epoch
+    pub last_played: i64,
+    // None when Riot does not report chests
+    pub chest_granted: Option<bool>,
+}
+
+// The full scoreboard of a finished match
+#[derive(Serialize, Debug)]
+pub struct MatchDetails {
+    pub match_id: String,
+    pub queue_id: u16,
+    pub queue: String,
+    pub map: String,
+    pub mode: String,
+    // Milliseconds since the epoch
+    pub started: i64,
+    // Seconds
+    pub duration: u32,
+    pub remake: bool,
+    pub teams: Vec<MatchTeam>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct MatchTeam {
+    // 100 for blue side, 200 for red side
+    pub team_id: u16,
+    pub win: bool,
+    pub players: Vec<PlayerStats>,
+}
+
+// A player's end of game stats
+#[derive(Serialize, Debug)]
+pub struct PlayerStats {
+    pub name: String,
+    pub champion_id: u16,
+    pub champion: String,
+    pub role: String,
+    pub level: u8,
+    pub kills: u16,
+    pub deaths: u16,
+    pub assists: u16,
+    pub cs: u16,
+    pub gold: u32,
+    pub damage: u32,
+    pub vision_score: u16,
+    pub items: Vec<String>,
+    pub keystone: String,
+    pub secondary_tree: String,
+}
+
+impl PlayerStats {
+    pub fn kda(&self) -> String {
+        format!("{}/{}/{}", self.kills, self.deaths, self.assists)
+    }
+}
+
+// The team a player is on in a live game
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum Side {
+    Blue,
+    Red,
+    // Arena pairs players into numbered teams
+    Subteam(u8),
+}
+
+impl Side {
+    // Team 100 starts bottom left on Summoner's Rift, the blue side
+    pub fn from_ids(team_id: u16, subteam_id: u8) -> Self {
+        match (team_id, subteam_id) {
+            (_, n) if n > 0 => Side::Subteam(n),
+            (200, _) => Side::Red,
+            _ => Side::Blue,
+        }
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            Side::Blue => String::from("Blue Team"),
+            Side::Red => String::from("Red Team"),
+            Side::Subteam(n) => format!("Team {}", n),
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct Participant {
+    pub side: Side,
+    pub puuid: String,
+    pub name: String,
+    pub champion_id: u16,
+    pub champion: String,
+    pub rank: Rank,
+    // Whether this is the player the game was looked up for
+    pub searched: bool,
+    // Summoner spells in D, F order
+    pub spells: Vec<String>,
+    pub keystone: String,
+    pub secondary_tree: String,
+    // Only looked up when asked for, it takes a match history lookup per player
+    pub champion_stats: Option<ChampionStats>,
+    // Players who queued together share a group number, solo players have none
+    pub premade: Option<u8>,
+}
+
+impl Participant {
+    pub fn new(side: Side, name: String, rank: Rank, champion_id: u16, champion: String) -> Self {
+        Participant {
+            side,
+            puuid: String::new(),
+            name,
+            champion_id,
+            champion,
+            rank,
+            searched: false,
+            spells: Vec::new(),
+            keystone: String::new(),
+            secondary_tree: String::new(),
+            champion_stats: None,
+            premade: None,
+        }
+    }
+}
+
+// The players on one side of a live game, however many there are
+#[derive(Serialize, Debug)]
+pub struct GameTeam {
+    pub side: Side,
+    pub players: Vec<Participant>,
+    // In pick order
+    pub bans: Vec<Ban>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Ban {
+    pub champion_id: u16,
+    pub champion: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Game {
+    // Blue before red, Arena teams by number
+    pub teams: Vec<GameTeam>,
+    pub mode: String,
+    pub game_type: String,
+    // 0 for custom games
+    pub queue_id: u16,
+    pub queue: String,
+    pub map_id: u16,
+    pub map: String,
+    // Milliseconds since the epoch, 0 while the players are loading in
+    pub started: i64,
+    // Seconds played when the game was looked up
+    pub length: u32,
+    // Whether the players were checked for premade groups, see Client::premades
+    pub premades_checked: bool,
+}
+
+impl Game {
+    // Groups the players and bans by side, keeping the order they were given in within a team
+    pub fn new(
+        players: Vec<Participant>,
+        bans: Vec<(Side, Ban)>,
+        mode: String,
+        game_type: String,
+    ) -> Self {
+        let mut teams: Vec<GameTeam> = Vec::new();
+        for player in players {
+            match teams.iter_mut().find(|t| t.side == player.side) {
+                Some(team) => team.players.push(player),
+                None => teams.push(GameTeam {
+                    side: player.side,
+                    players: vec![player],
+                    bans: Vec::new(),
+                }),
+            }
+        }
+        for (side, ban) in bans {
+            if let Some(team) = teams.iter_mut().find(|t| t.side == side) {
+                team.bans.push(ban);
+            }
+        }
+        teams.sort_by_key(|t| t.side);
+        Game {
+            teams,
+            mode,
+            game_type,
+            queue_id: 0,
+            queue: String::new(),
+            map_id: 0,
+            map: String::new(),
+            started: 0,
+            length: 0,
+            premades_checked: false,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Rank {
+    // Both are None when the player is unranked in the queue
+    pub tier: Option<Tier>,
+    #[serde(rename(deserialize = "rank"))]
+    pub division: Option<Division>,
+    #[serde(rename(deserialize = "queueType"))]
+    pub queue_type: RankedQueue,
+    pub wins: u32,
+    pub losses: u32,
+    #[serde(rename(deserialize = "hotStreak"))]
+    pub hot_streak: bool,
+    #[serde(rename(deserialize = "leaguePoints"))]
+    pub league_points: u16,
+}
+
+impl Rank {
+    pub fn unranked(queue_type: RankedQueue) -> Self {
+        Rank {
+            tier: None,
+            division: None,
+            queue_type,
+            wins: 0,
+            losses: 0,
+            hot_streak: false,
+            league_points: 0,
+        }
+    }
+
+    pub fn is_ranked(&self) -> bool {
+        self.tier.is_some()
+    }
+
+    // Picks the rank for games of a queue: its own ladder, solo queue for unranked queues
+    pub fn for_queue(ranks: &[Rank], queue_id: u16) -> Rank {
+        let queue_type = RankedQueue::for_queue_id(queue_id).unwrap_or(RankedQueue::SoloDuo);
+        ranks
+            .iter()
+            .find(|r| r.queue_type == queue_type)
+            .cloned()
+            .unwrap_or_else(|| Rank::unranked(queue_type))
+    }
+
+    // None when unranked, otherwise comparable with other ranks
+    pub fn ordinal(&self) -> Option<RankOrdinal> {
+        RankOrdinal::of(self)
+    }
+
+    pub fn win_rate(&self) -> Option<f32> {
+        match self.wins + self.losses {
+            0 => None,
+            played => Some(self.wins as f32 / played as f32 * 100.0),
+        }
+    }
+
+    // The abbreviated rank, e.g. "G2", "GM" or "N/A"
+    pub fn short(&self) -> String {
+        match (self.tier, self.division) {
+            (None, _) => String::from("N/A"),
+            (Some(tier), _) if tier.is_apex() => tier.short().to_string(),
+            (Some(tier), Some(division)) => format!("{}{}", tier.short(), division.number()),
+            (Some(tier), None) => tier.short().to_string(),
+        }
+    }
+}
+
+// Prints the full rank, e.g. "Gold II", "Master" or "Unranked"
+impl fmt::Display for Rank {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let rank = match (self.tier, self.division) {
+            (None, _) => String::from("Unranked"),
+            (Some(tier), Some(division)) if !tier.is_apex() => format!("{} {}", tier, division),
+            (Some(tier), _) => tier.to_string(),
+        };
+        f.pad(&rank)
+    }
+}
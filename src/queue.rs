@@ -0,0 +1,212 @@
+/// This file contains the queues, maps and game modes a match can be played in
+use crate::ddragon;
+use crate::error::ProgramError;
+use futures::future::join3;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+const QUEUES_FILE: &str = "queues.json";
+const MAPS_FILE: &str = "maps.json";
+const MODES_FILE: &str = "gameModes.json";
+
+#[allow(non_snake_case)]
+#[derive(Deserialize, Debug)]
+struct QueueJSON {
+    queueId: u16,
+    map: String,
+    // e.g. "5v5 Ranked Solo games", missing for some retired queues
+    description: Option<String>,
+}
+
+#[allow(non_snake_case)]
+#[derive(Deserialize, Debug)]
+struct MapJSON {
+    mapId: u16,
+    mapName: String,
+}
+
+#[allow(non_snake_case)]
+#[derive(Deserialize, Debug)]
+struct GameModeJSON {
+    gameMode: String,
+    description: String,
+}
+
+// The queues that can be picked on the command line, with shorter names than Riot's
+pub struct Queue {
+    pub id: u16,
+    // The name used on the command line, e.g. "ranked-solo"
+    pub slug: &'static str,
+    pub name: &'static str,
+}
+
+#[rustfmt::skip]
+pub const QUEUES: [Queue; 14] = [
+    Queue { id: 0, slug: "custom", name: "Custom" },
+    Queue { id: 400, slug: "normal-draft", name: "Normal Draft" },
+    Queue { id: 420, slug: "ranked-solo", name: "Ranked Solo" },
+    Queue { id: 430, slug: "normal-blind", name: "Normal Blind" },
+    Queue { id: 440, slug: "ranked-flex", name: "Ranked Flex" },
+    Queue { id: 450, slug: "aram", name: "ARAM" },
+    Queue { id: 480, slug: "swiftplay", name: "Swiftplay" },
+    Queue { id: 490, slug: "quickplay", name: "Quickplay" },
+    Queue { id: 700, slug: "clash", name: "Clash" },
+    Queue { id: 720, slug: "aram-clash", name: "ARAM Clash" },
+    Queue { id: 830, slug: "coop-intro", name: "Co-op vs AI Intro" },
+    Queue { id: 900, slug: "urf", name: "ARURF" },
+    Queue { id: 1700, slug: "arena", name: "Arena" },
+    Queue { id: 1900, slug: "pick-urf", name: "Pick URF" },
+];
+
+// The current maps, used until Riot's map list has been downloaded
+pub struct Map {
+    pub id: u16,
+    pub name: &'static str,
+}
+
+#[rustfmt::skip]
+pub const MAPS: [Map; 4] = [
+    Map { id: 11, name: "Summoner's Rift" },
+    Map { id: 12, name: "Howling Abyss" },
+    Map { id: 21, name: "Nexus Blitz" },
+    Map { id: 30, name: "Rings of Wrath" },
+];
+
+// Resolves a queue slug such as "aram", or a numeric queue id, to the queue id
+pub fn queue_id(value: &str) -> Option<u16> {
+    let value = value.trim().to_lowercase();
+    if let Ok(id) = value.parse::<u16>() {
+        return Some(id);
+    }
+    QUEUES.iter().find(|q| q.slug == value).map(|q| q.id)
+}
+
+// A queue from Riot's queue list
+#[derive(Debug, Clone)]
+pub struct QueueInfo {
+    pub id: u16,
+    pub name: String,
+    pub map: String,
+}
+
+// Every queue, map and game mode Riot documents, shared by all lookups
+pub struct Queues {
+    by_id: HashMap<u16, QueueInfo>,
+    maps: HashMap<u16, String>,
+    modes: HashMap<String, String>,
+}
+
+impl Queues {
+    // Uses the saved lists, downloading them on the first run. Only the bundled queues and maps
+    // are known when Riot's lists cannot be reached.
+    pub async fn load() -> Self {
+        let files = (
+            ddragon::read_file::<Vec<QueueJSON>>(QUEUES_FILE),
+            ddragon::read_file::<Vec<MapJSON>>(MAPS_FILE),
+            ddragon::read_file::<Vec<GameModeJSON>>(MODES_FILE),
+        );
+        if let (Some(queues), Some(maps), Some(modes)) = files {
+            return Self::from_files(queues, maps, modes);
+        }
+        match Self::refresh().await {
+            Ok(queues) => queues,
+            Err(_) => Self::from_files(Vec::new(), Vec::new(), Vec::new()),
+        }
+    }
+
+    pub async fn refresh() -> Result<Self, ProgramError> {
+        let (queues, maps, modes) = join3(
+            ddragon::fetch_docs::<Vec<QueueJSON>>(QUEUES_FILE),
+            ddragon::fetch_docs::<Vec<MapJSON>>(MAPS_FILE),
+            ddragon::fetch_docs::<Vec<GameModeJSON>>(MODES_FILE),
+        )
+        .await;
+        Ok(Self::from_files(queues?, maps?, modes?))
+    }
+
+    fn from_files(queues: Vec<QueueJSON>, maps: Vec<MapJSON>, modes: Vec<GameModeJSON>) -> Self {
+        let mut by_id: HashMap<u16, QueueInfo> = queues
+            .into_iter()
+            .map(|q| {
+                // "5v5 Ranked Solo games" reads as "5v5 Ranked Solo"
+                let name = q
+                    .description
+                    .as_ref()
+                    .map(|d| d.trim_end_matches(" games").to_string())
+                    .unwrap_or_else(|| format!("Queue {}", q.queueId));
+                let info = QueueInfo {
+                    id: q.queueId,
+                    name,
+                    map: q.map,
+                };
+                (q.queueId, info)
+            })
+            .collect();
+        // The bundled names are the ones used on the command line
+        for queue in QUEUES.iter() {
+            let info = by_id.entry(queue.id).or_insert_with(|| QueueInfo {
+                id: queue.id,
+                name: String::new(),
+                map: String::new(),
+            });
+            info.name = queue.name.to_string();
+        }
+
+        let mut by_map: HashMap<u16, String> =
+            MAPS.iter().map(|m| (m.id, m.name.to_string())).collect();
+        // Retired versions of a map are listed first, the current name wins
+        for map in maps {
+            by_map.insert(map.mapId, map.mapName);
+        }
+
+        let modes = modes
+            .into_iter()
+            .map(|m| (m.gameMode, m.description))
+            .collect();
+        Queues {
+            by_id,
+            maps: by_map,
+            modes,
+        }
+    }
+
+    pub fn name(&self, id: u16) -> String {
+        match self.by_id.get(&id) {
+            Some(queue) => queue.name.clone(),
+            None => format!("Queue {}", id),
+        }
+    }
+
+    pub fn map_name(&self, id: u16) -> String {
+        match self.maps.get(&id) {
+            Some(name) => name.clone(),
+            None => format!("Map {}", id),
+        }
+    }
+
+    // Riot's description of a game mode such as "ARAM", or the mode itself when it is unknown
+    pub fn mode_description(&self, mode: &str) -> String {
+        match self.modes.get(mode) {
+            Some(description) => description.clone(),
+            None => mode.to_string(),
+        }
+    }
+
+    // Every documented game mode with its description, ordered by mode
+    pub fn modes(&self) -> Vec<(&str, &str)> {
+        let mut modes: Vec<(&str, &str)> = self
+            .modes
+            .iter()
+            .map(|(mode, description)| (&mode[..], &description[..]))
+            .collect();
+        modes.sort();
+        modes
+    }
+
+    // Every known queue ordered by id
+    pub fn all(&self) -> Vec<&QueueInfo> {
+        let mut queues: Vec<&QueueInfo> = self.by_id.values().collect();
+        queues.sort_by_key(|q| q.id);
+        queues
+    }
+}
@@ -0,0 +1,334 @@
+/// This file contains the tiers, divisions and queues of ranked play and how ranks compare
+use crate::models::Rank;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+const DIVISION_LP: u32 = 100;
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Tier {
+    Iron,
+    Bronze,
+    Silver,
+    Gold,
+    Platinum,
+    Emerald,
+    Diamond,
+    Master,
+    Grandmaster,
+    Challenger,
+}
+
+impl Tier {
+    pub const ALL: [Tier; 10] = [
+        Tier::Iron,
+        Tier::Bronze,
+        Tier::Silver,
+        Tier::Gold,
+        Tier::Platinum,
+        Tier::Emerald,
+        Tier::Diamond,
+        Tier::Master,
+        Tier::Grandmaster,
+        Tier::Challenger,
+    ];
+
+    // Master and above have no divisions, their LP keeps counting from Master 0 LP
+    pub fn is_apex(self) -> bool {
+        self >= Tier::Master
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Tier::Iron => "Iron",
+            Tier::Bronze => "Bronze",
+            Tier::Silver => "Silver",
+            Tier::Gold => "Gold",
+            Tier::Platinum => "Platinum",
+            Tier::Emerald => "Emerald",
+            Tier::Diamond => "Diamond",
+            Tier::Master => "Master",
+            Tier::Grandmaster => "Grandmaster",
+            Tier::Challenger => "Challenger",
+        }
+    }
+
+    // The abbreviation players use, e.g. "G" for Gold or "GM" for Grandmaster
+    pub fn short(self) -> &'static str {
+        match self {
+            Tier::Iron => "I",
+            Tier::Bronze => "B",
+            Tier::Silver => "S",
+            Tier::Gold => "G",
+            Tier::Platinum => "P",
+            Tier::Emerald => "E",
+            Tier::Diamond => "D",
+            Tier::Master => "M",
+            Tier::Grandmaster => "GM",
+            Tier::Challenger => "C",
+        }
+    }
+}
+
+impl fmt::Display for Tier {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(self.name())
+    }
+}
+
+// Declared lowest first so that division I compares above division IV
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Division {
+    #[serde(rename = "IV")]
+    Four,
+    #[serde(rename = "III")]
+    Three,
+    #[serde(rename = "II")]
+    Two,
+    #[serde(rename = "I")]
+    One,
+}
+
+impl Division {
+    pub const ALL: [Division; 4] = [
+        Division::Four,
+        Division::Three,
+        Division::Two,
+        Division::One,
+    ];
+
+    pub fn numeral(self) -> &'static str {
+        match self {
+            Division::Four => "IV",
+            Division::Three => "III",
+            Division::Two => "II",
+            Division::One => "I",
+        }
+    }
+
+    pub fn number(self) -> u8 {
+        match self {
+            Division::Four => 4,
+            Division::Three => 3,
+            Division::Two => 2,
+            Division::One => 1,
+        }
+    }
+}
+
+impl fmt::Display for Division {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(self.numeral())
+    }
+}
+
+// The ranked ladders a player can be placed in, in the order they are listed
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum RankedQueue {
+    #[serde(rename = "RANKED_SOLO_5x5")]
+    SoloDuo,
+    #[serde(rename = "RANKED_FLEX_SR")]
+    Flex,
+    #[serde(rename = "CHERRY")]
+    Arena,
+    // Ladders added after this was written
+    #[serde(other, rename = "OTHER")]
+    Other,
+}
+
+impl RankedQueue {
+    // The ladder whose rank applies to games of a queue, e.g. 440 to Flex
+    pub fn for_queue_id(queue_id: u16) -> Option<Self> {
+        match queue_id {
+            420 => Some(RankedQueue::SoloDuo),
+            440 => Some(RankedQueue::Flex),
+            1700 => Some(RankedQueue::Arena),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            RankedQueue::SoloDuo => "Solo/Duo",
+            RankedQueue::Flex => "Flex",
+            RankedQueue::Arena => "Arena",
+            RankedQueue::Other => "Other",
+        }
+    }
+}
+
+impl fmt::Display for RankedQueue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(self.name())
+    }
+}
+
+// A rank as its tier, division and LP, ordered from Iron IV 0 LP upwards
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct RankOrdinal {
+    tier: Tier,
+    division: Division,
+    lp: u32,
+}
+
+impl RankOrdinal {
+    // None when the player is unranked
+    pub fn of(rank: &Rank) -> Option<Self> {
+        let tier = rank.tier?;
+        // Riot reports apex tiers as division I
+        let division = match tier.is_apex() {
+            true => Division::One,
+            false => rank.division?,
+        };
+        Some(RankOrdinal {
+            tier,
+            division,
+            lp: rank.league_points as u32,
+        })
+    }
+
+    // The LP needed to climb from Iron IV 0 LP to this rank
+    pub fn total_lp(&self) -> u32 {
+        let divisions = Division::ALL.len() as u32;
+        let steps = match self.tier.is_apex() {
+            true => Tier::Master as u32 * divisions,
+            false => self.tier as u32 * divisions + self.division as u32,
+        };
+        steps * DIVISION_LP + self.lp
+    }
+
+    // Master, Grandmaster and Challenger share one LP ladder, so totals past Master stay in Master
+    pub fn from_total_lp(total: u32) -> Self {
+        let divisions = Division::ALL.len() as u32;
+        let apex = Tier::Master as u32 * divisions;
+        let steps = total / DIVISION_LP;
+        if steps >= apex {
+            return RankOrdinal {
+                tier: Tier::Master,
+                division: Division::One,
+                lp: total - apex * DIVISION_LP,
+            };
+        }
+        RankOrdinal {
+            tier: Tier::ALL[(steps / divisions) as usize],
+            division: Division::ALL[(steps % divisions) as usize],
+            lp: total % DIVISION_LP,
+        }
+    }
+
+    // The average of the ranked players and how many there were, None when nobody is ranked
+    pub fn average<'a, I>(ranks: I) -> Option<(Self, usize)>
+    where
+        I: IntoIterator<Item = &'a Rank>,
+    {
+        let totals: Vec<u32> = ranks
+            .into_iter()
+            .filter_map(RankOrdinal::of)
+            .map(|r| r.total_lp())
+            .collect();
+        if totals.is_empty() {
+            return None;
+        }
+        let average = totals.iter().sum::<u32>() / totals.len() as u32;
+        Some((RankOrdinal::from_total_lp(average), totals.len()))
+    }
+}
+
+// Prints e.g. "Gold II (≈45 LP)" or "Master (≈120 LP)"
+impl fmt::Display for RankOrdinal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.tier.is_apex() {
+            return write!(f, "{} (≈{} LP)", self.tier, self.lp);
+        }
+        write!(f, "{} {} (≈{} LP)", self.tier, self.division, self.lp)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rank(tier: Tier, division: Division, lp: u16) -> Rank {
+        Rank {
+            tier: Some(tier),
+            division: Some(division),
+            league_points: lp,
+            ..Rank::unranked(RankedQueue::SoloDuo)
+        }
+    }
+
+    fn ordinal(tier: Tier, division: Division, lp: u16) -> RankOrdinal {
+        RankOrdinal::of(&rank(tier, division, lp)).unwrap()
+    }
+
+    #[test]
+    fn total_lp_round_trips() {
+        let gold = ordinal(Tier::Gold, Division::Two, 45);
+        assert_eq!(gold.total_lp(), 1445);
+        assert_eq!(RankOrdinal::from_total_lp(1445), gold);
+        assert_eq!(gold.to_string(), "Gold II (≈45 LP)");
+
+        let iron = ordinal(Tier::Iron, Division::Four, 0);
+        assert_eq!(iron.total_lp(), 0);
+        assert_eq!(RankOrdinal::from_total_lp(0), iron);
+
+        let diamond = ordinal(Tier::Diamond, Division::One, 99);
+        assert_eq!(RankOrdinal::from_total_lp(diamond.total_lp()), diamond);
+    }
+
+    #[test]
+    fn apex_tiers_share_the_master_ladder() {
+        let master = ordinal(Tier::Master, Division::One, 120);
+        assert_eq!(master.total_lp(), 2920);
+        assert_eq!(RankOrdinal::from_total_lp(2920), master);
+
+        // Grandmaster and Challenger come back as Master with the same LP
+        let grandmaster = ordinal(Tier::Grandmaster, Division::One, 300);
+        assert_eq!(
+            RankOrdinal::from_total_lp(grandmaster.total_lp()),
+            ordinal(Tier::Master, Division::One, 300)
+        );
+        let challenger = ordinal(Tier::Challenger, Division::One, 1000);
+        assert_eq!(
+            RankOrdinal::from_total_lp(challenger.total_lp()).to_string(),
+            "Master (≈1000 LP)"
+        );
+    }
+
+    #[test]
+    fn apex_division_is_ignored() {
+        // Riot reports apex tiers as division I, anything else is treated the same
+        let mut master = rank(Tier::Master, Division::Three, 50);
+        assert_eq!(RankOrdinal::of(&master).unwrap().total_lp(), 2850);
+        master.division = None;
+        assert_eq!(RankOrdinal::of(&master).unwrap().total_lp(), 2850);
+    }
+
+    #[test]
+    fn average_of_mixed_tiers() {
+        let ranks = vec![
+            rank(Tier::Gold, Division::Two, 45),
+            rank(Tier::Platinum, Division::Four, 55),
+            Rank::unranked(RankedQueue::SoloDuo),
+        ];
+        let (average, ranked) = RankOrdinal::average(&ranks).unwrap();
+        assert_eq!(average, ordinal(Tier::Gold, Division::One, 50));
+        assert_eq!(ranked, 2);
+
+        // Crossing into Master lands on Master 0 LP, not a fifth Diamond division
+        let ranks = vec![
+            rank(Tier::Diamond, Division::One, 90),
+            rank(Tier::Master, Division::One, 10),
+        ];
+        let (average, _) = RankOrdinal::average(&ranks).unwrap();
+        assert_eq!(average, ordinal(Tier::Master, Division::One, 0));
+    }
+
+    #[test]
+    fn average_without_ranked_players() {
+        let ranks = vec![Rank::unranked(RankedQueue::Flex)];
+        assert_eq!(RankOrdinal::average(&ranks), None);
+        assert_eq!(RankOrdinal::average(&[]), None);
+    }
+}
@@ -0,0 +1,176 @@
+/// This file contains the Riot platforms and the regional clusters they route to
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+pub const REGIONS: [Region; 17] = [
+    Region::Br1,
+    Region::Eun1,
+    Region::Euw1,
+    Region::Jp1,
+    Region::Kr,
+    Region::La1,
+    Region::La2,
+    Region::Me1,
+    Region::Na1,
+    Region::Oc1,
+    Region::Ph2,
+    Region::Ru,
+    Region::Sg2,
+    Region::Th2,
+    Region::Tr1,
+    Region::Tw2,
+    Region::Vn2,
+];
+
+// A platform routing value. Summoner, league and spectator endpoints are served per platform.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Region {
+    Br1,
+    Eun1,
+    Euw1,
+    Jp1,
+    Kr,
+    La1,
+    La2,
+    Me1,
+    #[default]
+    Na1,
+    Oc1,
+    Ph2,
+    Ru,
+    Sg2,
+    Th2,
+    Tr1,
+    Tw2,
+    Vn2,
+}
+
+// A regional routing value. Account and match endpoints are served per cluster.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cluster {
+    Americas,
+    Europe,
+    Asia,
+    Sea,
+}
+
+impl Region {
+    pub fn id(self) -> &'static str {
+        match self {
+            Region::Br1 => "br1",
+            Region::Eun1 => "eun1",
+            Region::Euw1 => "euw1",
+            Region::Jp1 => "jp1",
+            Region::Kr => "kr",
+            Region::La1 => "la1",
+            Region::La2 => "la2",
+            Region::Me1 => "me1",
+            Region::Na1 => "na1",
+            Region::Oc1 => "oc1",
+            Region::Ph2 => "ph2",
+            Region::Ru => "ru",
+            Region::Sg2 => "sg2",
+            Region::Th2 => "th2",
+            Region::Tr1 => "tr1",
+            Region::Tw2 => "tw2",
+            Region::Vn2 => "vn2",
+        }
+    }
+
+    pub fn host(self) -> String {
+        format!("https://{}.api.riotgames.com", self.id())
+    }
+
+    pub fn cluster(self) -> Cluster {
+        match self {
+            Region::Br1 | Region::La1 | Region::La2 | Region::Na1 => Cluster::Americas,
+            Region::Eun1 | Region::Euw1 | Region::Me1 | Region::Ru | Region::Tr1 => Cluster::Europe,
+            Region::Jp1 | Region::Kr => Cluster::Asia,
+            Region::Oc1 | Region::Ph2 | Region::Sg2 | Region::Th2 | Region::Tw2 | Region::Vn2 => {
+                Cluster::Sea
+            }
+        }
+    }
+
+    // Account-V1 is only served by the americas, asia and europe clusters
+    pub fn account_cluster(self) -> Cluster {
+        match self.cluster() {
+            Cluster::Sea => Cluster::Asia,
+            cluster => cluster,
+        }
+    }
+
+    // The tag line Riot assigned to players who migrated from a summoner name on this region
+    pub fn default_tag(self) -> &'static str {
+        match self {
+            Region::Br1 => "BR1",
+            Region::Eun1 => "EUNE",
+            Region::Euw1 => "EUW",
+            Region::Jp1 => "JP1",
+            Region::Kr => "KR1",
+            Region::La1 => "LAN",
+            Region::La2 => "LAS",
+            Region::Me1 => "ME1",
+            Region::Na1 => "NA1",
+            Region::Oc1 => "OCE",
+            Region::Ph2 => "PH2",
+            Region::Ru => "RU1",
+            Region::Sg2 => "SG2",
+            Region::Th2 => "TH2",
+            Region::Tr1 => "TR1",
+            Region::Tw2 => "TW2",
+            Region::Vn2 => "VN2",
+        }
+    }
+}
+
+impl fmt::Display for Region {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.id())
+    }
+}
+
+impl FromStr for Region {
+    type Err = ();
+
+    // Accepts the platform id ("euw1") as well as the short name players use ("euw")
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match &s.to_lowercase()[..] {
+            "br" | "br1" => Ok(Region::Br1),
+            "eune" | "eun1" => Ok(Region::Eun1),
+            "euw" | "euw1" => Ok(Region::Euw1),
+            "jp" | "jp1" => Ok(Region::Jp1),
+            "kr" => Ok(Region::Kr),
+            "lan" | "la1" => Ok(Region::La1),
+            "las" | "la2" => Ok(Region::La2),
+            "me" | "me1" => Ok(Region::Me1),
+            "na" | "na1" => Ok(Region::Na1),
+            "oce" | "oc1" => Ok(Region::Oc1),
+            "ph" | "ph2" => Ok(Region::Ph2),
+            "ru" => Ok(Region::Ru),
+            "sg" | "sg2" => Ok(Region::Sg2),
+            "th" | "th2" => Ok(Region::Th2),
+            "tr" | "tr1" => Ok(Region::Tr1),
+            "tw" | "tw2" => Ok(Region::Tw2),
+            "vn" | "vn2" => Ok(Region::Vn2),
+            _ => Err(()),
+        }
+    }
+}
+
+impl Cluster {
+    pub fn id(self) -> &'static str {
+        match self {
+            Cluster::Americas => "americas",
+            Cluster::Europe => "europe",
+            Cluster::Asia => "asia",
+            Cluster::Sea => "sea",
+        }
+    }
+
+    pub fn host(self) -> String {
+        format!("https://{}.api.riotgames.com", self.id())
+    }
+}
@@ -0,0 +1,68 @@
+/// This file contains the rune names from Data Dragon
+use crate::ddragon;
+use crate::error::ProgramError;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+pub const DEFAULT_RUNE: &str = "Unknown Rune";
+const RUNES_FILE: &str = "runesReforged.json";
+
+#[derive(Deserialize, Debug)]
+struct RuneTreeJSON {
+    id: u16,
+    name: String,
+    slots: Vec<RuneSlotJSON>,
+}
+
+#[derive(Deserialize, Debug)]
+struct RuneSlotJSON {
+    runes: Vec<RuneJSON>,
+}
+
+#[derive(Deserialize, Debug)]
+struct RuneJSON {
+    id: u16,
+    name: String,
+}
+
+// Rune and rune tree names by id
+pub struct Runes {
+    by_id: HashMap<u16, String>,
+}
+
+impl Runes {
+    // Uses the saved Data Dragon file, downloading the one of `version`, or of the latest patch,
+    // on the first run. The names are left unknown when Data Dragon cannot be reached.
+    pub async fn load(version: Option<&str>) -> Self {
+        if let Some(file) = ddragon::read_file::<Vec<RuneTreeJSON>>(RUNES_FILE) {
+            return Self::from_file(file);
+        }
+        match Self::refresh(version).await {
+            Ok(runes) => runes,
+            Err(_) => Runes {
+                by_id: HashMap::new(),
+            },
+        }
+    }
+
+    pub async fn refresh(version: Option<&str>) -> Result<Self, ProgramError> {
+        let file: Vec<RuneTreeJSON> = ddragon::fetch(RUNES_FILE, version).await?;
+        Ok(Self::from_file(file))
+    }
+
+    fn from_file(file: Vec<RuneTreeJSON>) -> Self {
+        let mut by_id = HashMap::new();
+        for tree in file {
+            for rune in tree.slots.into_iter().flat_map(|s| s.runes) {
+                by_id.insert(rune.id, rune.name);
+            }
+            by_id.insert(tree.id, tree.name);
+        }
+        Runes { by_id }
+    }
+
+    // The name of a rune or of a rune tree
+    pub fn name(&self, id: u16) -> &str {
+        self.by_id.get(&id).map(|n| &n[..]).unwrap_or(DEFAULT_RUNE)
+    }
+}
@@ -0,0 +1,61 @@
+/// This file contains the summoner spell names from Data Dragon
+use crate::ddragon;
+use crate::error::ProgramError;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+const SPELLS_FILE: &str = "summoner.json";
+
+#[derive(Deserialize, Debug)]
+struct SpellFileJSON {
+    data: HashMap<String, SpellJSON>,
+}
+
+#[derive(Deserialize, Debug)]
+struct SpellJSON {
+    // The numeric id as a string, e.g. "4" for Flash
+    key: String,
+    name: String,
+}
+
+// Summoner spell names by id
+pub struct Spells {
+    by_id: HashMap<u16, String>,
+}
+
+impl Spells {
+    // Uses the saved Data Dragon file, downloading the one of `version`, or of the latest patch,
+    // on the first run. Unknown spells are shown by id when Data Dragon cannot be reached.
+    pub async fn load(version: Option<&str>) -> Self {
+        if let Some(file) = ddragon::read_file::<SpellFileJSON>(SPELLS_FILE) {
+            return Self::from_file(file);
+        }
+        match Self::refresh(version).await {
+            Ok(spells) => spells,
+            Err(_) => Spells {
+                by_id: HashMap::new(),
+            },
+        }
+    }
+
+    pub async fn refresh(version: Option<&str>) -> Result<Self, ProgramError> {
+        let file: SpellFileJSON = ddragon::fetch(SPELLS_FILE, version).await?;
+        Ok(Self::from_file(file))
+    }
+
+    fn from_file(file: SpellFileJSON) -> Self {
+        let by_id = file
+            .data
+            .into_values()
+            .filter_map(|spell| Some((spell.key.parse().ok()?, spell.name)))
+            .collect();
+        Spells { by_id }
+    }
+
+    pub fn name(&self, id: u16) -> String {
+        match self.by_id.get(&id) {
+            Some(name) => name.clone(),
+            None => format!("Spell {}", id),
+        }
+    }
+}